@@ -0,0 +1,271 @@
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek};
+use std::str;
+
+use crate::SubCursor;
+
+const MAGIC: &[u8; 8] = b"!<arch>\n";
+const HEADER_LEN: usize = 60;
+const HEADER_SIZE: u64 = HEADER_LEN as u64;
+
+/// A member of an `ar` archive and its location in the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArEntry {
+    name: String,
+    mtime: u64,
+    uid: u32,
+    gid: u32,
+    mode: u32,
+    header_offset: u64,
+    data_offset: u64,
+    size: u64,
+}
+
+impl ArEntry {
+    /// Returns the name of the entry. Long names are resolved through the GNU
+    /// name table or the BSD `#1/` prefix.
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Returns the modification time in seconds since the unix epoch.
+    pub const fn mtime(&self) -> u64 { self.mtime }
+
+    /// Returns the user id of the owner.
+    pub const fn uid(&self) -> u32 { self.uid }
+
+    /// Returns the group id of the owner.
+    pub const fn gid(&self) -> u32 { self.gid }
+
+    /// Returns the permissions of the entry.
+    pub const fn mode(&self) -> u32 { self.mode }
+
+    /// Returns the offset of the header of the entry.
+    pub const fn header_offset(&self) -> u64 { self.header_offset }
+
+    /// Returns the offset of the data of the entry.
+    pub const fn data_offset(&self) -> u64 { self.data_offset }
+
+    /// Returns the size of the data of the entry.
+    pub const fn size(&self) -> u64 { self.size }
+}
+
+/// An [`ArArchive`] lists the members of an `ar` archive (like static
+/// libraries or debian packages), that is located in a [`SubCursor`].
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{ArArchive, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::Read;
+///
+/// let mut data = b"!<arch>\n".to_vec();
+/// data.extend_from_slice(b"debian-binary   0           0     0     100644  4         `\n");
+/// data.extend_from_slice(b"2.0\n");
+///
+/// let archive = ArArchive::new(SubCursor::from(data))?;
+/// let entry = archive.by_name("debian-binary").unwrap();
+///
+/// let mut result = String::new();
+/// archive.data(entry)?.read_to_string(&mut result)?;
+/// assert_eq!(result, "2.0\n".to_string());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Note
+///
+/// The GNU and BSD variants (with long names) are supported. The symbol
+/// tables of static libraries are not listed as entries.
+#[derive(Debug, Clone)]
+pub struct ArArchive<T> {
+    sub_cursor: SubCursor<T>,
+    entries: Vec<ArEntry>,
+}
+
+impl<T> ArArchive<T>
+where
+    T: Read + Seek,
+{
+    /// Reads all headers of the `ar` archive in the [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the magic or a header is invalid or if
+    /// the [`SubCursor`] could not be read.
+    pub fn new(sub_cursor: SubCursor<T>) -> io::Result<Self> {
+        let len = sub_cursor.len() as u64;
+
+        let mut magic = [0; 8];
+        sub_cursor.read_exact_at(0, &mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("invalid ar magic"));
+        }
+
+        let mut entries = vec![];
+        let mut names = None;
+
+        let mut offset = MAGIC.len() as u64;
+        while offset + HEADER_SIZE <= len {
+            let mut header = [0; HEADER_LEN];
+            sub_cursor.read_exact_at(offset, &mut header)?;
+
+            if &header[58..60] != b"`\n" {
+                return Err(invalid_data(&format!("invalid header at {offset}")));
+            }
+
+            let header_offset = offset;
+            let mut data_offset = offset + HEADER_SIZE;
+            let mut size = parse_number(&header[48..58], 10)?;
+            // the data has to end inside of the archive and is aligned to an
+            // even offset
+            offset = data_offset
+                .checked_add(size)
+                .filter(|end| *end <= len)
+                .map(|end| end + size % 2)
+                .ok_or_else(|| {
+                    invalid_data(&format!(
+                        "the data at {data_offset} is out of bounds (size: {size})"
+                    ))
+                })?;
+
+            let field = str::from_utf8(&header[..16])
+                .map_err(|_| invalid_data("invalid name in a header"))?
+                .trim_end_matches(' ');
+
+            let name = match field {
+                // the symbol tables of GNU
+                "/" | "/SYM64/" => continue,
+                // the GNU table of long names
+                "//" => {
+                    names = Some(read_data(&sub_cursor, data_offset, size)?);
+                    continue;
+                }
+                _ if field.starts_with("#1/") => {
+                    // BSD stores the name in front of the data
+                    let name_len = parse_number(&field.as_bytes()[3..], 10)?;
+                    if name_len > size {
+                        return Err(invalid_data("the name is longer than the entry"));
+                    }
+
+                    let name = read_data(&sub_cursor, data_offset, name_len)?;
+                    data_offset += name_len;
+                    size -= name_len;
+
+                    let end = name
+                        .iter()
+                        .position(|byte| *byte == 0)
+                        .unwrap_or(name.len());
+                    String::from_utf8_lossy(&name[..end]).into_owned()
+                }
+                _ if field.starts_with('/') => {
+                    let names = names
+                        .as_ref()
+                        .ok_or_else(|| invalid_data("missing table of long names"))?;
+
+                    let start = parse_number(&field.as_bytes()[1..], 10)?;
+                    let name = usize::try_from(start)
+                        .ok()
+                        .and_then(|start| names.get(start..))
+                        .ok_or_else(|| invalid_data("invalid offset of a long name"))?;
+
+                    let end = name
+                        .iter()
+                        .position(|byte| *byte == b'\n')
+                        .unwrap_or(name.len());
+                    String::from_utf8_lossy(&name[..end])
+                        .trim_end_matches('/')
+                        .to_string()
+                }
+                // GNU terminates names with a slash
+                _ => field.trim_end_matches('/').to_string(),
+            };
+
+            // the symbol tables of BSD, which might have a long name
+            if name == "__.SYMDEF" || name == "__.SYMDEF SORTED" {
+                continue;
+            }
+
+            entries.push(ArEntry {
+                name,
+                mtime: parse_number(&header[16..28], 10)?,
+                uid: parse_u32(&header[28..34], 10)?,
+                gid: parse_u32(&header[34..40], 10)?,
+                mode: parse_u32(&header[40..48], 8)?,
+                header_offset,
+                data_offset,
+                size,
+            });
+        }
+
+        Ok(Self {
+            sub_cursor,
+            entries,
+        })
+    }
+
+    /// Returns the data of `entry` as a [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the data is out of bounds.
+    pub fn data(&self, entry: &ArEntry) -> io::Result<SubCursor<T>> {
+        self.sub_cursor
+            .child(entry.data_offset, entry.size)
+            .ok_or_else(|| invalid_data("the data of the entry is out of bounds"))
+    }
+}
+
+impl<T> ArArchive<T> {
+    /// Returns all entries in the order of the archive.
+    pub fn entries(&self) -> &[ArEntry] { &self.entries }
+
+    /// Returns the first entry with the provided name.
+    pub fn by_name(&self, name: &str) -> Option<&ArEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Returns the number of entries.
+    pub const fn len(&self) -> usize { self.entries.len() }
+
+    /// Returns `true`, if there are no entries.
+    pub const fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// Consumes this [`ArArchive`], returning the [`SubCursor`].
+    pub fn into_inner(self) -> SubCursor<T> { self.sub_cursor }
+}
+
+/// Parses a space padded number of a header.
+fn parse_number(field: &[u8], radix: u32) -> io::Result<u64> {
+    let text = str::from_utf8(field)
+        .map_err(|_| invalid_data("invalid number in a header"))?
+        .trim_end_matches(' ');
+
+    if text.is_empty() {
+        return Ok(0);
+    }
+
+    u64::from_str_radix(text, radix).map_err(|_| invalid_data("invalid number in a header"))
+}
+
+/// Parses a space padded number of a header, that has to fit in an `u32`.
+fn parse_u32(field: &[u8], radix: u32) -> io::Result<u32> {
+    u32::try_from(parse_number(field, radix)?)
+        .map_err(|_| invalid_data("invalid number in a header"))
+}
+
+/// Reads `len` bytes at `offset`, the data has to be complete.
+fn read_data<T: Read + Seek>(
+    sub_cursor: &SubCursor<T>,
+    offset: u64,
+    len: u64,
+) -> io::Result<Vec<u8>> {
+    let len =
+        usize::try_from(len).map_err(|_| invalid_data("the data of the entry is too large"))?;
+
+    let mut buffer = vec![0; len];
+    sub_cursor.read_exact_at(offset, &mut buffer)?;
+    Ok(buffer)
+}
+
+fn invalid_data(message: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, message) }
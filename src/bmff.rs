@@ -0,0 +1,245 @@
+use std::io::{self, Read, Seek};
+
+use crate::endian::Endian::Big;
+use crate::SubCursor;
+
+impl<T> SubCursor<T>
+where
+    T: Read + Seek,
+{
+    /// Returns an iterator over the ISO base media file format boxes (like in
+    /// MP4, MOV or HEIF files), that are directly inside of the
+    /// [`SubCursor`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let mut data = b"\0\0\0\x10ftypisom\0\0\0\0".to_vec();
+    /// data.extend_from_slice(b"\0\0\0\x10moov\0\0\0\x08trak");
+    /// // the last box extends to the end of the file
+    /// data.extend_from_slice(b"\0\0\0\0mdatsamples");
+    ///
+    /// let boxes = SubCursor::from(data)
+    ///     .iso_boxes()
+    ///     .map(|iso_box| iso_box.map(|iso_box| (iso_box.kind(), iso_box.data().len())))
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(boxes, vec![(*b"ftyp", 8), (*b"moov", 8), (*b"mdat", 7)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// The boxes are not read, until they are needed, so the [`IsoBox::data`]
+    /// of a large `mdat` box is never read, if only the `moov` box is
+    /// inspected.
+    pub fn iso_boxes(&self) -> IsoBoxes<T> {
+        IsoBoxes {
+            sub_cursor: self.sub_cursor(),
+            offset: Some(0),
+        }
+    }
+}
+
+/// A box of an ISO base media file, created by [`IsoBoxes`].
+#[derive(Debug, Clone)]
+pub struct IsoBox<T> {
+    kind: [u8; 4],
+    user_type: Option<[u8; 16]>,
+    offset: u64,
+    header_size: u64,
+    data: SubCursor<T>,
+}
+
+impl<T> IsoBox<T> {
+    /// Returns the four character code of the box (like `moov`).
+    pub const fn kind(&self) -> [u8; 4] { self.kind }
+
+    /// Returns the extended type of a `uuid` box or `None` for other boxes.
+    pub const fn user_type(&self) -> Option<[u8; 16]> { self.user_type }
+
+    /// Returns the offset of the box in the [`SubCursor`], that contains the
+    /// box.
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    pub const fn offset(&self) -> u64 { self.offset }
+
+    /// Returns the size of the header, which is 8 bytes and 8 more bytes
+    /// for a 64-bit size and 16 more bytes for a `uuid` box.
+    pub const fn header_size(&self) -> u64 { self.header_size }
+
+    /// Returns the size of the box (including the header).
+    pub const fn size(&self) -> u64 { self.header_size + self.data.len() as u64 }
+
+    /// Returns the payload of the box (without the header).
+    pub const fn data(&self) -> &SubCursor<T> { &self.data }
+}
+
+impl<T> IsoBox<T>
+where
+    T: Read + Seek,
+{
+    /// Returns an iterator over the boxes inside of the payload of a
+    /// container box (like `moov` or `trak`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let data = b"\0\0\0\x10moov\0\0\0\x08trak".to_vec();
+    ///
+    /// let moov = SubCursor::from(data).iso_boxes().next().unwrap()?;
+    /// let trak = moov.children().next().unwrap()?;
+    ///
+    /// assert_eq!(trak.kind(), *b"trak");
+    /// assert_eq!(trak.offset(), 0);
+    /// assert_eq!(trak.data().get_start(), 16);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn children(&self) -> IsoBoxes<T> { self.data.iso_boxes() }
+
+    /// Returns an iterator over the boxes inside of the payload of a
+    /// container box, that starts at `offset`. Some boxes have fields in
+    /// front of the boxes (like `stsd` or `meta`).
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if `offset` is after the end of the
+    /// payload.
+    pub fn children_from(&self, offset: u64) -> io::Result<IsoBoxes<T>> {
+        let sub_cursor = (self.data.len() as u64)
+            .checked_sub(offset)
+            .and_then(|len| self.data.child(offset, len))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the offset is after the end of the box",
+                )
+            })?;
+
+        Ok(sub_cursor.iso_boxes())
+    }
+
+    /// Reads the version and the flags of a full box (like `mvhd` or
+    /// `meta`), which are the first 4 bytes of the payload.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let data = b"\0\0\0\x0cmeta\x01\0\0\x02".to_vec();
+    ///
+    /// let meta = SubCursor::from(data).iso_boxes().next().unwrap()?;
+    /// assert_eq!(meta.full_box_header()?, (1, 2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the payload is shorter than 4 bytes.
+    pub fn full_box_header(&self) -> io::Result<(u8, u32)> {
+        let mut header = [0; 4];
+        self.data.read_exact_at(0, &mut header)?;
+
+        Ok((header[0], Big.u32(&header) & 0x00ff_ffff))
+    }
+}
+
+/// An iterator over the boxes of an ISO base media file, created by
+/// [`SubCursor::iso_boxes`].
+///
+/// [`SubCursor::iso_boxes`]: crate::SubCursor::iso_boxes
+#[derive(Debug, Clone)]
+pub struct IsoBoxes<T> {
+    sub_cursor: SubCursor<T>,
+    // the offset of the next box or `None`, if the iterator is exhausted
+    offset: Option<u64>,
+}
+
+impl<T> IsoBoxes<T>
+where
+    T: Read + Seek,
+{
+    fn next_box(&mut self, offset: u64) -> io::Result<Option<IsoBox<T>>> {
+        let len = self.sub_cursor.len() as u64;
+        if offset >= len {
+            return Ok(None);
+        }
+
+        let mut header = [0; 8];
+        self.sub_cursor.read_exact_at(offset, &mut header)?;
+
+        let kind = [header[4], header[5], header[6], header[7]];
+        let mut header_size = 8;
+
+        let size = match Big.u32(&header) {
+            // the box extends to the end of the file
+            0 => len - offset,
+            1 => {
+                let mut large_size = [0; 8];
+                self.sub_cursor
+                    .read_exact_at(offset + header_size, &mut large_size)?;
+                header_size += 8;
+
+                Big.u64(&large_size)
+            }
+            size => u64::from(size),
+        };
+
+        let user_type = {
+            if &kind == b"uuid" {
+                let mut user_type = [0; 16];
+                self.sub_cursor
+                    .read_exact_at(offset + header_size, &mut user_type)?;
+                header_size += 16;
+
+                Some(user_type)
+            } else {
+                None
+            }
+        };
+
+        let data = size
+            .checked_sub(header_size)
+            .and_then(|data_size| self.sub_cursor.child(offset + header_size, data_size))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("the box at {offset} has an invalid size"),
+                )
+            })?;
+
+        self.offset = Some(offset + size);
+
+        Ok(Some(IsoBox {
+            kind,
+            user_type,
+            offset,
+            header_size,
+            data,
+        }))
+    }
+}
+
+impl<T> Iterator for IsoBoxes<T>
+where
+    T: Read + Seek,
+{
+    type Item = io::Result<IsoBox<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset.take()?;
+
+        self.next_box(offset).transpose()
+    }
+}
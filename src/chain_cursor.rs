@@ -0,0 +1,214 @@
+use std::cmp;
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::iter::FromIterator;
+
+use crate::utils::seek_position;
+use crate::SubCursor;
+
+/// A [`ChainCursor`] concatenates many [`SubCursor`]s (extents) into one
+/// continuous stream.
+///
+/// The extents can point to different underlying streams, which is useful for
+/// split archives or fragmented files.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{ChainCursor, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::{Read, Seek, SeekFrom};
+///
+/// let first = SubCursor::from(b"##Hello ##".to_vec()).start(2).end(8);
+/// let second = SubCursor::from(b"World!".to_vec());
+///
+/// let mut chain_cursor = ChainCursor::new(vec![first, second]);
+/// assert_eq!(chain_cursor.len(), 12);
+///
+/// let mut result = String::new();
+/// chain_cursor.read_to_string(&mut result)?;
+/// assert_eq!(result, "Hello World!".to_string());
+///
+/// // reads can cross the boundaries of the extents
+/// chain_cursor.seek(SeekFrom::Start(4))?;
+/// let mut buffer = [0; 4];
+/// chain_cursor.read_exact(&mut buffer)?;
+/// assert_eq!(&buffer, b"o Wo");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default, Debug, Clone)]
+pub struct ChainCursor<T> {
+    extents: Vec<SubCursor<T>>,
+    // the offset of every extent in the chain, sorted in ascending order
+    offsets: Vec<u64>,
+    len: u64,
+    position: u64,
+}
+
+impl<T> ChainCursor<T> {
+    /// Creates a new [`ChainCursor`] from the provided extents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{ChainCursor, SubCursor};
+    /// let chain_cursor = ChainCursor::new(vec![
+    ///     SubCursor::from(vec![1, 2, 3]),
+    ///     SubCursor::from(vec![4, 5]),
+    /// ]);
+    ///
+    /// assert_eq!(chain_cursor.len(), 5);
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// The length of every extent is determined by its [`start`] and [`end`]
+    /// when the [`ChainCursor`] is created.
+    ///
+    /// [`start`]: crate::SubCursor::start
+    /// [`end`]: crate::SubCursor::end
+    pub fn new(extents: Vec<SubCursor<T>>) -> Self {
+        let mut offsets = Vec::with_capacity(extents.len());
+        let mut len = 0;
+
+        for extent in &extents {
+            offsets.push(len);
+            len += extent.len() as u64;
+        }
+
+        Self {
+            extents,
+            offsets,
+            len,
+            position: 0,
+        }
+    }
+
+    /// Returns the combined length of all extents.
+    pub const fn len(&self) -> u64 { self.len }
+
+    /// Returns `true`, if the combined length of all extents is `0`.
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns the current position in the chain.
+    pub const fn position(&self) -> u64 { self.position }
+
+    /// Returns all extents of this [`ChainCursor`].
+    pub fn extents(&self) -> &[SubCursor<T>] { &self.extents }
+
+    /// Consumes this [`ChainCursor`], returning the extents.
+    pub fn into_inner(self) -> Vec<SubCursor<T>> { self.extents }
+
+    /// Returns the index of the extent, that contains `position` and the
+    /// offset inside of that extent.
+    fn locate(&self, position: u64) -> Option<(usize, u64)> {
+        if position >= self.len {
+            return None;
+        }
+
+        // the last extent, that starts at or before the position
+        // (empty extents are skipped, because the following extent has the
+        // same offset)
+        let index = self.offsets.partition_point(|offset| *offset <= position) - 1;
+
+        Some((index, position - self.offsets[index]))
+    }
+}
+
+impl<T> FromIterator<SubCursor<T>> for ChainCursor<T> {
+    fn from_iter<I: IntoIterator<Item = SubCursor<T>>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<T> Seek for ChainCursor<T> {
+    /// Seek to the provided position.
+    ///
+    /// # Error
+    ///
+    /// This function will error, if you attempt to seek before 0.
+    ///
+    /// # Note
+    ///
+    /// Unlike a [`SubCursor`], it is possible to seek past the end. Reads and
+    /// writes after the end will return `0`.
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        self.position = seek_position(self.position, self.len, style)?;
+        Ok(self.position)
+    }
+}
+
+impl<T> Read for ChainCursor<T>
+where
+    T: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut result = 0;
+
+        while result < buf.len() {
+            let Some((index, offset)) = self.locate(self.position) else {
+                break;
+            };
+
+            let extent = &mut self.extents[index];
+            let available = usize::try_from(extent.len() as u64 - offset).unwrap_or(usize::MAX);
+            let remaining = cmp::min(buf.len() - result, available);
+
+            extent.set_position(offset);
+            let read = extent.read(&mut buf[result..result + remaining])?;
+
+            // the underlying stream is shorter than the extent
+            if read == 0 {
+                break;
+            }
+
+            result += read;
+            self.position += read as u64;
+        }
+
+        Ok(result)
+    }
+}
+
+impl<T> Write for ChainCursor<T>
+where
+    T: Write + Seek,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut result = 0;
+
+        while result < buf.len() {
+            let Some((index, offset)) = self.locate(self.position) else {
+                break;
+            };
+
+            let extent = &mut self.extents[index];
+            let available = usize::try_from(extent.len() as u64 - offset).unwrap_or(usize::MAX);
+            let remaining = cmp::min(buf.len() - result, available);
+
+            extent.set_position(offset);
+            let written = extent.write(&buf[result..result + remaining])?;
+
+            if written == 0 {
+                break;
+            }
+
+            result += written;
+            self.position += written as u64;
+        }
+
+        Ok(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        for extent in &mut self.extents {
+            extent.flush()?;
+        }
+
+        Ok(())
+    }
+}
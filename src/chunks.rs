@@ -0,0 +1,244 @@
+use std::cmp;
+use std::convert::TryFrom;
+use std::io;
+
+use crate::SubCursor;
+
+impl<T> SubCursor<T> {
+    /// Divides the [`SubCursor`] into two at `offset` (relative to the
+    /// start).
+    ///
+    /// The first [`SubCursor`] will contain all bytes before `offset` and the
+    /// second one all bytes starting at `offset`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let sub_cursor = SubCursor::from(vec![1, 2, 3, 4, 5, 6]).start(1);
+    /// let (left, right) = sub_cursor.split_at(2)?;
+    ///
+    /// assert_eq!((left.get_start(), left.get_end()), (1, 3));
+    /// assert_eq!((right.get_start(), right.get_end()), (3, 6));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if `offset` is larger than the length of the
+    /// [`SubCursor`].
+    pub fn split_at(&self, offset: usize) -> io::Result<(Self, Self)> {
+        let len = self.len();
+
+        match (
+            self.child(0, offset as u64),
+            self.child(offset as u64, len.saturating_sub(offset) as u64),
+        ) {
+            (Some(left), Some(right)) => Ok((left, right)),
+            _ => Err(out_of_bounds(offset, len)),
+        }
+    }
+
+    /// Divides the [`SubCursor`] at every offset (relative to the start).
+    ///
+    /// This will return `offsets.len() + 1` [`SubCursor`]s.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let sub_cursor = SubCursor::from(vec![0; 10]);
+    /// let parts = sub_cursor.split_at_offsets(&[2, 5, 5])?;
+    ///
+    /// assert_eq!(
+    ///     parts.iter().map(|part| part.len()).collect::<Vec<_>>(),
+    ///     vec![2, 3, 0, 5]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the offsets are not sorted in ascending
+    /// order, if an offset does not fit in a `usize` or if an offset is larger
+    /// than the length of the [`SubCursor`].
+    pub fn split_at_offsets(&self, offsets: &[u64]) -> io::Result<Vec<Self>> {
+        let len = self.len();
+        let mut result = Vec::with_capacity(offsets.len() + 1);
+        let mut previous = 0;
+
+        for offset in offsets.iter().copied().chain(Some(len as u64)) {
+            let offset = usize::try_from(offset).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("offset {offset} does not fit in a usize"),
+                )
+            })?;
+
+            if offset < previous {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the offsets have to be sorted in ascending order",
+                ));
+            }
+
+            let part = self
+                .child(previous as u64, (offset - previous) as u64)
+                .ok_or_else(|| out_of_bounds(offset, len))?;
+
+            result.push(part);
+            previous = offset;
+        }
+
+        Ok(result)
+    }
+
+    /// Returns an iterator over `size` bytes long [`SubCursor`]s, the last
+    /// [`SubCursor`] might be shorter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// let sub_cursor = SubCursor::from(vec![0; 10]);
+    ///
+    /// assert_eq!(
+    ///     sub_cursor
+    ///         .chunks(4)
+    ///         .map(|chunk| chunk.len())
+    ///         .collect::<Vec<_>>(),
+    ///     vec![4, 4, 2]
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn chunks(&self, size: usize) -> Chunks<T> {
+        assert_ne!(size, 0, "the size of a chunk must not be 0");
+
+        Chunks {
+            sub_cursor: self.sub_cursor(),
+            size,
+            offset: 0,
+        }
+    }
+
+    /// Returns an iterator over `size` bytes long [`SubCursor`]s, the
+    /// remaining bytes can be retrieved with [`ChunksExact::remainder`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// let sub_cursor = SubCursor::from(vec![0; 10]);
+    /// let chunks = sub_cursor.chunks_exact(4);
+    ///
+    /// assert_eq!(chunks.remainder().len(), 2);
+    /// assert_eq!(
+    ///     chunks.map(|chunk| chunk.len()).collect::<Vec<_>>(),
+    ///     vec![4, 4]
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// [`ChunksExact::remainder`]: crate::ChunksExact::remainder
+    pub fn chunks_exact(&self, size: usize) -> ChunksExact<T> {
+        assert_ne!(size, 0, "the size of a chunk must not be 0");
+
+        // both parts are created from the same bounds, the offset is never
+        // larger than the length
+        let (exact, remainder) = self
+            .split_at(self.len() - self.len() % size)
+            .expect("the offset is in bounds");
+
+        ChunksExact {
+            chunks: Chunks {
+                sub_cursor: exact,
+                size,
+                offset: 0,
+            },
+            remainder,
+        }
+    }
+}
+
+fn out_of_bounds(offset: usize, len: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("offset {offset} is out of bounds (length: {len})"),
+    )
+}
+
+/// An iterator over [`SubCursor`]s with a fixed size, created by
+/// [`SubCursor::chunks`].
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`SubCursor::chunks`]: crate::SubCursor::chunks
+#[derive(Debug, Clone)]
+pub struct Chunks<T> {
+    sub_cursor: SubCursor<T>,
+    size: usize,
+    offset: usize,
+}
+
+impl<T> Iterator for Chunks<T> {
+    type Item = SubCursor<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.sub_cursor.len();
+        if self.offset >= len {
+            return None;
+        }
+
+        let size = cmp::min(self.size, len - self.offset);
+        let result = self.sub_cursor.child(self.offset as u64, size as u64);
+        self.offset += size;
+
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.sub_cursor.len().saturating_sub(self.offset);
+        let result = remaining.div_ceil(self.size);
+
+        (result, Some(result))
+    }
+}
+
+impl<T> ExactSizeIterator for Chunks<T> {}
+
+/// An iterator over [`SubCursor`]s with a fixed size, created by
+/// [`SubCursor::chunks_exact`].
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`SubCursor::chunks_exact`]: crate::SubCursor::chunks_exact
+#[derive(Debug, Clone)]
+pub struct ChunksExact<T> {
+    chunks: Chunks<T>,
+    remainder: SubCursor<T>,
+}
+
+impl<T> ChunksExact<T> {
+    /// Returns the remaining bytes, that are not part of a chunk.
+    pub fn remainder(&self) -> SubCursor<T> { self.remainder.sub_cursor() }
+}
+
+impl<T> Iterator for ChunksExact<T> {
+    type Item = SubCursor<T>;
+
+    fn next(&mut self) -> Option<Self::Item> { self.chunks.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.chunks.size_hint() }
+}
+
+impl<T> ExactSizeIterator for ChunksExact<T> {}
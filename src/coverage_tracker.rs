@@ -0,0 +1,364 @@
+use std::collections::BTreeMap;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+/// A [`CoverageTracker`] wraps a stream and counts how often every byte has
+/// been read.
+///
+/// This is useful for reverse engineering file formats: a (half finished)
+/// parser can read from [`SubCursor`]s sharing the tracker and afterwards the
+/// [`gaps`] show, which parts of the file have never been touched.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{CoverageTracker, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::{Cursor, Read};
+/// use std::sync::{Arc, Mutex};
+///
+/// let tracker = Arc::new(Mutex::new(CoverageTracker::new(Cursor::new(vec![0; 40]))));
+///
+/// let mut header = SubCursor::from(tracker.clone()).end(8);
+/// let mut body = SubCursor::from(tracker.clone()).start(20).end(30);
+///
+/// header.read_exact(&mut [0; 8])?;
+/// body.read_exact(&mut [0; 10])?;
+///
+/// let tracker = tracker.lock().unwrap();
+/// assert_eq!(tracker.gaps(40), vec![8..20, 30..40]);
+/// assert_eq!(tracker.render(40, 20), "####......#####.....".to_string());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`gaps`]: #method.gaps
+#[derive(Default, Debug, Clone)]
+pub struct CoverageTracker<T> {
+    inner: T,
+    position: u64,
+    // maps the start of a range to its end and how often it has been read,
+    // the ranges never overlap.
+    coverage: BTreeMap<u64, (u64, u64)>,
+}
+
+impl<T> CoverageTracker<T>
+where
+    T: Seek,
+{
+    /// Creates a new [`CoverageTracker`], that wraps `inner`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::CoverageTracker;
+    /// use std::io::Cursor;
+    ///
+    /// let tracker = CoverageTracker::new(Cursor::new(vec![1, 2, 3]));
+    ///
+    /// assert_eq!(tracker.gaps(3), vec![0..3]);
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// If the current position of `inner` can not be determined, `0` will be
+    /// assumed.
+    pub fn new(mut inner: T) -> Self {
+        Self {
+            position: inner.stream_position().unwrap_or(0),
+            inner,
+            coverage: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> CoverageTracker<T> {
+    /// Returns all absolute byte ranges, that have been read, together with
+    /// the number of times they have been read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::CoverageTracker;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::{Cursor, Read, Seek, SeekFrom};
+    ///
+    /// let mut tracker = CoverageTracker::new(Cursor::new(vec![0; 10]));
+    ///
+    /// tracker.read_exact(&mut [0; 4])?;
+    /// tracker.seek(SeekFrom::Start(2))?;
+    /// tracker.read_exact(&mut [0; 4])?;
+    ///
+    /// assert_eq!(tracker.read_ranges(), vec![(0..2, 1), (2..4, 2), (4..6, 1)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_ranges(&self) -> Vec<(Range<u64>, u64)> {
+        self.coverage
+            .iter()
+            .map(|(start, (end, count))| (*start..*end, *count))
+            .collect()
+    }
+
+    /// Returns all ranges in `0..len`, that have never been read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::CoverageTracker;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::{Cursor, Read, Seek, SeekFrom};
+    ///
+    /// let mut tracker = CoverageTracker::new(Cursor::new(vec![0; 10]));
+    ///
+    /// tracker.seek(SeekFrom::Start(4))?;
+    /// tracker.read_exact(&mut [0; 2])?;
+    ///
+    /// assert_eq!(tracker.gaps(10), vec![0..4, 6..10]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn gaps(&self, len: u64) -> Vec<Range<u64>> {
+        let mut result = vec![];
+        let mut position = 0;
+
+        for (start, (end, _)) in self.coverage.range(..len) {
+            if *start > position {
+                result.push(position..*start);
+            }
+            position = *end;
+        }
+
+        if position < len {
+            result.push(position..len);
+        }
+
+        result
+    }
+
+    /// Renders a textual map of the coverage of `0..len` with `width`
+    /// characters.
+    ///
+    /// Every character represents `len / width` bytes and is either `#` (all
+    /// bytes have been read), `+` (some bytes have been read) or `.` (no byte
+    /// has been read).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::CoverageTracker;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::{Cursor, Read};
+    ///
+    /// let mut tracker = CoverageTracker::new(Cursor::new(vec![0; 10]));
+    ///
+    /// tracker.read_exact(&mut [0; 5])?;
+    ///
+    /// assert_eq!(tracker.render(10, 5), "##+..".to_string());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn render(&self, len: u64, width: usize) -> String {
+        (0..width as u64)
+            .map(|column| {
+                let start = column * len / width as u64;
+                let end = ((column + 1) * len / width as u64).max(start + 1);
+
+                let read = self.covered(start..end);
+                if read == 0 {
+                    '.'
+                } else if read == end - start {
+                    '#'
+                } else {
+                    '+'
+                }
+            })
+            .collect()
+    }
+
+    /// Forgets all recorded reads.
+    pub fn clear_coverage(&mut self) { self.coverage.clear() }
+
+    /// Returns a reference to the underlying stream.
+    pub const fn get_ref(&self) -> &T { &self.inner }
+
+    /// Returns a mutable reference to the underlying stream.
+    ///
+    /// # Note
+    ///
+    /// Reads from the returned reference will not be tracked and seeking it
+    /// will confuse the [`CoverageTracker`].
+    pub const fn get_mut(&mut self) -> &mut T { &mut self.inner }
+
+    /// Consumes the [`CoverageTracker`], returning the underlying stream.
+    pub fn into_inner(self) -> T { self.inner }
+
+    /// Returns the number of bytes in `range`, that have been read at least
+    /// once.
+    fn covered(&self, range: Range<u64>) -> u64 {
+        // the range before `range.start` might overlap:
+        let previous = self.coverage.range(..range.start).next_back().into_iter();
+
+        previous
+            .chain(self.coverage.range(range.clone()))
+            .map(|(start, (end, _))| {
+                (*end)
+                    .min(range.end)
+                    .saturating_sub((*start).max(range.start))
+            })
+            .sum()
+    }
+
+    /// Splits the range, that contains `offset`, into two ranges.
+    fn split(&mut self, offset: u64) {
+        let found = self
+            .coverage
+            .range(..offset)
+            .next_back()
+            .map(|(start, value)| (*start, *value));
+
+        if let Some((start, (end, count))) = found {
+            if end > offset {
+                self.coverage.insert(start, (offset, count));
+                self.coverage.insert(offset, (end, count));
+            }
+        }
+    }
+
+    /// Increments the read count of every byte in `range`.
+    fn record(&mut self, range: Range<u64>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        self.split(range.start);
+        self.split(range.end);
+
+        let mut position = range.start;
+        let mut changes = vec![];
+
+        for (start, (end, count)) in self.coverage.range(range.clone()) {
+            if *start > position {
+                changes.push((position, (*start, 1)));
+            }
+            changes.push((*start, (*end, count + 1)));
+            position = *end;
+        }
+
+        if position < range.end {
+            changes.push((position, (range.end, 1)));
+        }
+
+        for (start, value) in changes {
+            self.coverage.insert(start, value);
+        }
+
+        self.merge(range);
+    }
+
+    /// Merges adjacent ranges with the same count in and around `range`.
+    fn merge(&mut self, range: Range<u64>) {
+        let first = self
+            .coverage
+            .range(..range.start)
+            .next_back()
+            .map_or(range.start, |(start, _)| *start);
+
+        let entries = self
+            .coverage
+            .range(first..=range.end)
+            .map(|(start, value)| (*start, *value))
+            .collect::<Vec<_>>();
+
+        let mut previous: Option<(u64, (u64, u64))> = None;
+        for (start, (end, count)) in entries {
+            match previous {
+                Some((previous_start, (previous_end, previous_count)))
+                    if previous_end == start && previous_count == count =>
+                {
+                    self.coverage.remove(&start);
+                    self.coverage.insert(previous_start, (end, count));
+                    previous = Some((previous_start, (end, count)));
+                }
+                _ => previous = Some((start, (end, count))),
+            }
+        }
+    }
+}
+
+impl<T> Seek for CoverageTracker<T>
+where
+    T: Seek,
+{
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(style)?;
+        Ok(self.position)
+    }
+}
+
+impl<T> Read for CoverageTracker<T>
+where
+    T: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = self.inner.read(buf)?;
+
+        self.record(self.position..self.position + result as u64);
+        self.position += result as u64;
+
+        Ok(result)
+    }
+}
+
+impl<T> Write for CoverageTracker<T>
+where
+    T: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let result = self.inner.write(buf)?;
+        self.position += result as u64;
+        Ok(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_record() {
+        let mut tracker = CoverageTracker::new(Cursor::new(vec![]));
+
+        tracker.record(10..20);
+        tracker.record(30..40);
+        tracker.record(15..35);
+        assert_eq!(
+            tracker.read_ranges(),
+            vec![
+                (10..15, 1),
+                (15..20, 2),
+                (20..30, 1),
+                (30..35, 2),
+                (35..40, 1)
+            ]
+        );
+
+        // the counts are equal again, so the ranges should be merged
+        tracker.record(10..15);
+        tracker.record(20..30);
+        tracker.record(35..40);
+        assert_eq!(tracker.read_ranges(), vec![(10..40, 2)]);
+
+        assert_eq!(tracker.gaps(50), vec![0..10, 40..50]);
+        assert_eq!(tracker.covered(5..15), 5);
+    }
+}
@@ -0,0 +1,60 @@
+/// The lookup table of the reflected polynomial, which is built at compile
+/// time.
+static TABLE: [u32; 256] = build_table();
+
+const fn build_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut index = 0_u32;
+
+    while index < 256 {
+        let mut value = index;
+        let mut bit = 0;
+        while bit < 8 {
+            value = if value & 1 == 1 {
+                0xEDB8_8320 ^ (value >> 1)
+            } else {
+                value >> 1
+            };
+            bit += 1;
+        }
+        table[index as usize] = value;
+        index += 1;
+    }
+
+    table
+}
+
+/// Computes the CRC-32 (ISO-HDLC), which is used by PNG, GPT and ZIP.
+#[derive(Debug, Clone)]
+pub struct Crc32 {
+    value: u32,
+}
+
+impl Crc32 {
+    pub const fn new() -> Self { Self { value: 0xFFFF_FFFF } }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            let index = (self.value ^ u32::from(*byte)) & 0xFF;
+            self.value = TABLE[index as usize] ^ (self.value >> 8);
+        }
+    }
+
+    pub const fn finish(&self) -> u32 { self.value ^ 0xFFFF_FFFF }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(Crc32::new().finish(), 0);
+
+        let mut crc32 = Crc32::new();
+        crc32.update(b"1234");
+        crc32.update(b"56789");
+        assert_eq!(crc32.finish(), 0xCBF4_3926);
+    }
+}
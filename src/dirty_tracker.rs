@@ -0,0 +1,177 @@
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+use crate::range_set::RangeSet;
+
+/// A [`DirtyTracker`] wraps a stream and remembers every byte range, that has
+/// been written to it.
+///
+/// This is useful, if the underlying stream is shared by many [`SubCursor`]s
+/// and only the modified parts should be synced.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{DirtyTracker, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::{Cursor, Write};
+/// use std::sync::{Arc, Mutex};
+///
+/// let tracker = Arc::new(Mutex::new(DirtyTracker::new(Cursor::new(vec![0; 100]))));
+///
+/// let mut first = SubCursor::from(tracker.clone()).start(10).end(20);
+/// let mut second = SubCursor::from(tracker.clone()).start(20).end(40);
+///
+/// first.write_all(&[1; 10])?;
+/// second.write_all(&[2; 5])?;
+///
+/// assert_eq!(tracker.lock().unwrap().dirty_ranges(), vec![10..25]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`SubCursor`]: crate::SubCursor
+#[derive(Default, Debug, Clone)]
+pub struct DirtyTracker<T> {
+    inner: T,
+    position: u64,
+    dirty: RangeSet,
+}
+
+impl<T> DirtyTracker<T>
+where
+    T: Seek,
+{
+    /// Creates a new [`DirtyTracker`], that wraps `inner`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::DirtyTracker;
+    /// use std::io::Cursor;
+    ///
+    /// let tracker = DirtyTracker::new(Cursor::new(vec![1, 2, 3]));
+    ///
+    /// assert!(!tracker.is_dirty());
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// If the current position of `inner` can not be determined, `0` will be
+    /// assumed.
+    pub fn new(mut inner: T) -> Self {
+        Self {
+            position: inner.stream_position().unwrap_or(0),
+            inner,
+            dirty: RangeSet::new(),
+        }
+    }
+}
+
+impl<T> DirtyTracker<T> {
+    /// Returns all absolute byte ranges, that have been written since the
+    /// creation or the last call to [`clear_dirty`], in ascending order.
+    /// Adjacent and overlapping ranges are merged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::DirtyTracker;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::{Cursor, Seek, SeekFrom, Write};
+    ///
+    /// let mut tracker = DirtyTracker::new(Cursor::new(vec![]));
+    ///
+    /// tracker.write_all(b"abc")?;
+    /// tracker.seek(SeekFrom::Start(10))?;
+    /// tracker.write_all(b"def")?;
+    ///
+    /// assert_eq!(tracker.dirty_ranges(), vec![0..3, 10..13]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`clear_dirty`]: #method.clear_dirty
+    pub fn dirty_ranges(&self) -> Vec<Range<u64>> { self.dirty.to_vec() }
+
+    /// Returns `true`, if anything has been written since the creation or the
+    /// last call to [`clear_dirty`].
+    ///
+    /// [`clear_dirty`]: #method.clear_dirty
+    pub fn is_dirty(&self) -> bool { !self.dirty.is_empty() }
+
+    /// Forgets all recorded ranges.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::DirtyTracker;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::{Cursor, Write};
+    ///
+    /// let mut tracker = DirtyTracker::new(Cursor::new(vec![]));
+    ///
+    /// tracker.write_all(b"abc")?;
+    /// tracker.clear_dirty();
+    ///
+    /// assert!(!tracker.is_dirty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clear_dirty(&mut self) { self.dirty.clear() }
+
+    /// Returns a reference to the underlying stream.
+    pub const fn get_ref(&self) -> &T { &self.inner }
+
+    /// Returns a mutable reference to the underlying stream.
+    ///
+    /// # Note
+    ///
+    /// Writes to the returned reference will not be tracked and seeking it
+    /// will confuse the [`DirtyTracker`].
+    pub const fn get_mut(&mut self) -> &mut T { &mut self.inner }
+
+    /// Consumes the [`DirtyTracker`], returning the underlying stream.
+    pub fn into_inner(self) -> T { self.inner }
+}
+
+impl<T> Seek for DirtyTracker<T>
+where
+    T: Seek,
+{
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(style)?;
+        Ok(self.position)
+    }
+}
+
+impl<T> Read for DirtyTracker<T>
+where
+    T: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = self.inner.read(buf)?;
+        self.position += result as u64;
+        Ok(result)
+    }
+}
+
+impl<T> Write for DirtyTracker<T>
+where
+    T: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let result = self.inner.write(buf)?;
+
+        self.dirty
+            .insert(self.position..self.position + result as u64);
+        self.position += result as u64;
+
+        Ok(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
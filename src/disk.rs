@@ -0,0 +1,433 @@
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek};
+
+use crate::crc32::Crc32;
+use crate::endian::Endian::Little;
+use crate::SubCursor;
+
+const DEFAULT_SECTOR_SIZE: u64 = 512;
+const GPT_SIGNATURE: &[u8; 8] = b"EFI PART";
+/// The maximum size of the GPT partition entries (the default are 128
+/// entries of 128 bytes).
+const MAX_GPT_ENTRIES_LEN: u64 = 1024 * 1024;
+/// The type of the partition, that protects a GPT disk from old tools.
+const PROTECTIVE_MBR: u8 = 0xEE;
+
+/// A partition of a [`PartitionTable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition {
+    number: usize,
+    first_lba: u64,
+    sector_count: u64,
+    bootable: bool,
+    mbr_type: Option<u8>,
+    type_guid: Option<[u8; 16]>,
+    guid: Option<[u8; 16]>,
+    attributes: u64,
+    name: String,
+}
+
+impl Partition {
+    /// Returns the number of the partition, which starts at 1. The logical
+    /// partitions of an MBR start at 5 (like `/dev/sda5` on linux).
+    pub const fn number(&self) -> usize { self.number }
+
+    /// Returns the first sector of the partition.
+    pub const fn first_lba(&self) -> u64 { self.first_lba }
+
+    /// Returns the number of sectors of the partition.
+    pub const fn sector_count(&self) -> u64 { self.sector_count }
+
+    /// Returns `true`, if an MBR partition is marked as active.
+    pub const fn is_bootable(&self) -> bool { self.bootable }
+
+    /// Returns the type of an MBR partition (like `0x83` for linux) or
+    /// `None` for a GPT partition.
+    pub const fn mbr_type(&self) -> Option<u8> { self.mbr_type }
+
+    /// Returns the type of a GPT partition (in the byte order of the disk) or
+    /// `None` for an MBR partition.
+    pub const fn type_guid(&self) -> Option<[u8; 16]> { self.type_guid }
+
+    /// Returns the unique id of a GPT partition (in the byte order of the
+    /// disk) or `None` for an MBR partition.
+    pub const fn guid(&self) -> Option<[u8; 16]> { self.guid }
+
+    /// Returns the attributes of a GPT partition.
+    pub const fn attributes(&self) -> u64 { self.attributes }
+
+    /// Returns the name of a GPT partition.
+    pub fn name(&self) -> &str { &self.name }
+}
+
+/// A [`PartitionTable`] reads the MBR or the GPT of a disk image, that is
+/// located in a [`SubCursor`].
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{PartitionTable, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::Read;
+///
+/// let mut image = vec![0; 4 * 512];
+/// // a linux partition from sector 2 with 2 sectors
+/// image[446 + 4] = 0x83;
+/// image[446 + 8] = 2;
+/// image[446 + 12] = 2;
+/// image[510..512].copy_from_slice(&[0x55, 0xAA]);
+/// image[1024..1029].copy_from_slice(b"ext4!");
+///
+/// let table = PartitionTable::new(SubCursor::from(image))?;
+/// assert!(!table.is_gpt());
+///
+/// let partition = &table.partitions()[0];
+/// assert_eq!(partition.mbr_type(), Some(0x83));
+///
+/// let mut data = vec![];
+/// table.data(partition)?.read_to_end(&mut data)?;
+/// assert_eq!(data.len(), 1024);
+/// assert_eq!(&data[..5], b"ext4!");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Note
+///
+/// The MBR (with extended and logical partitions) and the GPT (with the
+/// backup header at the end of the disk, if the primary header or its
+/// partition entries are corrupted) are supported.
+#[derive(Debug, Clone)]
+pub struct PartitionTable<T> {
+    sub_cursor: SubCursor<T>,
+    sector_size: u64,
+    is_gpt: bool,
+    is_backup: bool,
+    disk_guid: Option<[u8; 16]>,
+    partitions: Vec<Partition>,
+}
+
+impl<T> PartitionTable<T>
+where
+    T: Read + Seek,
+{
+    /// Reads the partition table of the disk image in the [`SubCursor`],
+    /// which has 512 byte sectors.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if there is no valid MBR, if both GPT
+    /// headers are invalid or if the [`SubCursor`] could not be read.
+    pub fn new(sub_cursor: SubCursor<T>) -> io::Result<Self> {
+        Self::with_sector_size(sub_cursor, DEFAULT_SECTOR_SIZE)
+    }
+
+    /// Reads the partition table of the disk image in the [`SubCursor`],
+    /// which has sectors of `sector_size` bytes (like 4096 bytes for some
+    /// disks).
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if `sector_size` is less than 512, not a
+    /// power of two or larger than the disk image, if there is no valid MBR,
+    /// if both GPT headers are invalid or if the [`SubCursor`] could not be
+    /// read.
+    pub fn with_sector_size(sub_cursor: SubCursor<T>, sector_size: u64) -> io::Result<Self> {
+        if sector_size < 512 || !sector_size.is_power_of_two() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("the sector size {sector_size} is not a power of two of at least 512"),
+            ));
+        }
+
+        if sector_size > sub_cursor.len() as u64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("the sector size {sector_size} is larger than the disk image"),
+            ));
+        }
+
+        let mut table = Self {
+            sub_cursor,
+            sector_size,
+            is_gpt: false,
+            is_backup: false,
+            disk_guid: None,
+            partitions: vec![],
+        };
+
+        let mbr = table.read_mbr(0)?;
+
+        if mbr.iter().any(|entry| entry.kind == PROTECTIVE_MBR) {
+            table.is_gpt = true;
+
+            let (header, partitions) = match table.read_gpt(1) {
+                Ok(gpt) => gpt,
+                Err(ref error) if error.kind() == io::ErrorKind::InvalidData => {
+                    table.is_backup = true;
+
+                    let last_lba = (table.sub_cursor.len() as u64 / sector_size).saturating_sub(1);
+                    table.read_gpt(last_lba)?
+                }
+                Err(error) => return Err(error),
+            };
+
+            table.disk_guid = Some(header);
+            table.partitions = partitions;
+        } else {
+            table.partitions = table.read_mbr_partitions(&mbr)?;
+        }
+
+        Ok(table)
+    }
+
+    /// Returns the data of `partition` as a [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the partition is out of bounds.
+    pub fn data(&self, partition: &Partition) -> io::Result<SubCursor<T>> {
+        partition
+            .first_lba
+            .checked_mul(self.sector_size)
+            .and_then(|offset| {
+                Some((
+                    offset,
+                    partition.sector_count.checked_mul(self.sector_size)?,
+                ))
+            })
+            .and_then(|(offset, len)| self.sub_cursor.child(offset, len))
+            .ok_or_else(|| invalid_data("the partition is out of bounds"))
+    }
+
+    /// Reads the four partition entries of the MBR or EBR at `lba`.
+    fn read_mbr(&self, lba: u64) -> io::Result<Vec<MbrEntry>> {
+        let mut sector = [0; 512];
+        self.sub_cursor
+            .read_exact_at(self.sector_offset(lba)?, &mut sector)?;
+
+        if sector[510..] != [0x55, 0xAA] {
+            return Err(invalid_data(&format!("invalid boot signature at {lba}")));
+        }
+
+        Ok(sector[446..510]
+            .chunks_exact(16)
+            .map(|entry| {
+                MbrEntry {
+                    bootable: entry[0] == 0x80,
+                    kind: entry[4],
+                    first_lba: u64::from(Little.u32(&entry[8..])),
+                    sector_count: u64::from(Little.u32(&entry[12..])),
+                }
+            })
+            .collect())
+    }
+
+    fn read_mbr_partitions(&self, mbr: &[MbrEntry]) -> io::Result<Vec<Partition>> {
+        let mut partitions = vec![];
+        let mut logical = vec![];
+
+        for (index, entry) in mbr.iter().enumerate() {
+            if entry.kind == 0 {
+                continue;
+            }
+
+            partitions.push(entry.partition(index + 1, 0));
+
+            if entry.is_extended() && logical.is_empty() {
+                // every EBR describes a logical partition (relative to the
+                // EBR) and the next EBR (relative to the extended partition)
+                let mut visited = HashSet::new();
+                let mut next = Some(entry.first_lba);
+
+                while let Some(lba) = next.take() {
+                    if !visited.insert(lba) {
+                        return Err(invalid_data("the extended partitions contain a loop"));
+                    }
+
+                    let ebr = self.read_mbr(lba)?;
+                    if ebr[0].kind != 0 {
+                        logical.push(ebr[0].partition(5 + logical.len(), lba));
+                    }
+
+                    if ebr[1].is_extended() {
+                        next = Some(entry.first_lba + ebr[1].first_lba);
+                    }
+                }
+            }
+        }
+
+        partitions.extend(logical);
+        Ok(partitions)
+    }
+
+    /// Reads and verifies the GPT header at `lba` and its partition entries.
+    fn read_gpt(&self, lba: u64) -> io::Result<([u8; 16], Vec<Partition>)> {
+        // the sector is not larger than the disk image
+        let mut sector = vec![0; usize::try_from(self.sector_size).unwrap_or(usize::MAX)];
+        self.sub_cursor
+            .read_exact_at(self.sector_offset(lba)?, &mut sector)?;
+
+        if &sector[..8] != GPT_SIGNATURE {
+            return Err(invalid_data(&format!("invalid gpt signature at {lba}")));
+        }
+
+        let header_size = usize::try_from(Little.u32(&sector[12..]))
+            .ok()
+            .filter(|size| *size >= 92 && *size <= sector.len())
+            .ok_or_else(|| invalid_data("invalid size of the gpt header"))?;
+
+        // the checksum is computed with a zeroed checksum field
+        let header_crc = Little.u32(&sector[16..]);
+        sector[16..20].copy_from_slice(&[0; 4]);
+
+        let mut crc32 = Crc32::new();
+        crc32.update(&sector[..header_size]);
+        if crc32.finish() != header_crc {
+            return Err(invalid_data(&format!(
+                "invalid crc of the gpt header at {lba}"
+            )));
+        }
+
+        let mut disk_guid = [0; 16];
+        disk_guid.copy_from_slice(&sector[56..72]);
+
+        let entries_lba = Little.u64(&sector[72..]);
+        let entry_count = u64::from(Little.u32(&sector[80..]));
+        let entry_size = usize::try_from(Little.u32(&sector[84..]))
+            .ok()
+            .filter(|size| *size >= 128)
+            .ok_or_else(|| invalid_data("invalid size of a gpt partition entry"))?;
+
+        // the header is easy to forge, so the entries might be anywhere and
+        // of any size; an invalid location is treated like a corrupted header,
+        // so the backup header is used
+        let entries_offset = self.sector_offset(entries_lba)?;
+        let len = entry_count
+            .checked_mul(entry_size as u64)
+            .filter(|len| *len <= MAX_GPT_ENTRIES_LEN)
+            .filter(|len| {
+                entries_offset
+                    .checked_add(*len)
+                    .is_some_and(|end| end <= self.sub_cursor.len() as u64)
+            })
+            .and_then(|len| usize::try_from(len).ok())
+            .ok_or_else(|| invalid_data("the gpt partition entries are out of bounds"))?;
+
+        let mut entries = vec![0; len];
+        self.sub_cursor
+            .read_exact_at(entries_offset, &mut entries)?;
+
+        let mut crc32 = Crc32::new();
+        crc32.update(&entries);
+        if crc32.finish() != Little.u32(&sector[88..]) {
+            return Err(invalid_data(&format!(
+                "invalid crc of the gpt partition entries at {entries_lba}"
+            )));
+        }
+
+        let partitions = entries
+            .chunks_exact(entry_size)
+            .enumerate()
+            // unused entries have no type
+            .filter(|(_, entry)| entry[..16].iter().any(|byte| *byte != 0))
+            .map(|(index, entry)| {
+                let mut type_guid = [0; 16];
+                type_guid.copy_from_slice(&entry[..16]);
+                let mut guid = [0; 16];
+                guid.copy_from_slice(&entry[16..32]);
+
+                let first_lba = Little.u64(&entry[32..]);
+                let last_lba = Little.u64(&entry[40..]);
+
+                // the last sector is inclusive
+                let sector_count = last_lba
+                    .checked_add(1)
+                    .ok_or_else(|| invalid_data("invalid last sector of a gpt partition"))?
+                    .saturating_sub(first_lba);
+
+                let name = entry[56..128]
+                    .chunks_exact(2)
+                    .map(|unit| Little.u16(unit))
+                    .take_while(|unit| *unit != 0)
+                    .collect::<Vec<_>>();
+
+                Ok(Partition {
+                    number: index + 1,
+                    first_lba,
+                    sector_count,
+                    bootable: false,
+                    mbr_type: None,
+                    type_guid: Some(type_guid),
+                    guid: Some(guid),
+                    attributes: Little.u64(&entry[48..]),
+                    name: String::from_utf16_lossy(&name),
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok((disk_guid, partitions))
+    }
+
+    /// Returns the offset of the sector `lba`.
+    fn sector_offset(&self, lba: u64) -> io::Result<u64> {
+        lba.checked_mul(self.sector_size)
+            .ok_or_else(|| invalid_data(&format!("sector {lba} is out of bounds")))
+    }
+}
+
+impl<T> PartitionTable<T> {
+    /// Returns the size of a sector in bytes.
+    pub const fn sector_size(&self) -> u64 { self.sector_size }
+
+    /// Returns `true` for a GPT and `false` for an MBR.
+    pub const fn is_gpt(&self) -> bool { self.is_gpt }
+
+    /// Returns `true`, if the primary GPT header (or its partition entries)
+    /// is corrupted and the backup header at the end of the disk was used.
+    pub const fn is_backup(&self) -> bool { self.is_backup }
+
+    /// Returns the id of a GPT disk (in the byte order of the disk) or `None`
+    /// for an MBR.
+    pub const fn disk_guid(&self) -> Option<[u8; 16]> { self.disk_guid }
+
+    /// Returns all partitions. The logical partitions of an MBR are returned
+    /// after the primary partitions.
+    pub fn partitions(&self) -> &[Partition] { &self.partitions }
+
+    /// Consumes this [`PartitionTable`], returning the [`SubCursor`].
+    pub fn into_inner(self) -> SubCursor<T> { self.sub_cursor }
+}
+
+/// A partition entry of an MBR or an EBR.
+#[derive(Debug, Clone, Copy)]
+struct MbrEntry {
+    bootable: bool,
+    kind: u8,
+    first_lba: u64,
+    sector_count: u64,
+}
+
+impl MbrEntry {
+    fn is_extended(self) -> bool { [0x05, 0x0F, 0x85].contains(&self.kind) }
+
+    /// Converts the entry to a [`Partition`], whose first sector is relative
+    /// to `base`.
+    const fn partition(self, number: usize, base: u64) -> Partition {
+        Partition {
+            number,
+            first_lba: base + self.first_lba,
+            sector_count: self.sector_count,
+            bootable: self.bootable,
+            mbr_type: Some(self.kind),
+            type_guid: None,
+            guid: None,
+            attributes: 0,
+            name: String::new(),
+        }
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, message) }
@@ -0,0 +1,463 @@
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek};
+
+use crate::endian::Endian;
+use crate::SubCursor;
+
+/// The type of a section, that does not occupy space in the file (like
+/// `.bss`).
+const SHT_NOBITS: u32 = 8;
+
+/// A section of an ELF file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfSection {
+    name: String,
+    kind: u32,
+    flags: u64,
+    address: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    align: u64,
+    entry_size: u64,
+}
+
+impl ElfSection {
+    /// Returns the name of the section (like `.text`), which is resolved
+    /// through the section header string table (`.shstrtab`).
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Returns the type of the section (`sh_type`).
+    pub const fn kind(&self) -> u32 { self.kind }
+
+    /// Returns the flags of the section (`sh_flags`).
+    pub const fn flags(&self) -> u64 { self.flags }
+
+    /// Returns the virtual address of the section in memory.
+    pub const fn address(&self) -> u64 { self.address }
+
+    /// Returns the offset of the section in the file.
+    pub const fn offset(&self) -> u64 { self.offset }
+
+    /// Returns the size of the section. A section of the type `SHT_NOBITS`
+    /// (like `.bss`) does not occupy this space in the file.
+    pub const fn size(&self) -> u64 { self.size }
+
+    /// Returns the index of a related section (`sh_link`).
+    pub const fn link(&self) -> u32 { self.link }
+
+    /// Returns extra information, that depends on the type (`sh_info`).
+    pub const fn info(&self) -> u32 { self.info }
+
+    /// Returns the alignment of the section.
+    pub const fn align(&self) -> u64 { self.align }
+
+    /// Returns the size of an entry for sections, that contain a table (like
+    /// `.symtab`).
+    pub const fn entry_size(&self) -> u64 { self.entry_size }
+}
+
+/// A segment of an ELF file, that is described by a program header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfSegment {
+    kind: u32,
+    flags: u32,
+    offset: u64,
+    virtual_address: u64,
+    physical_address: u64,
+    file_size: u64,
+    memory_size: u64,
+    align: u64,
+}
+
+impl ElfSegment {
+    /// Returns the type of the segment (`p_type`, like `PT_LOAD` or
+    /// `PT_NOTE`).
+    pub const fn kind(&self) -> u32 { self.kind }
+
+    /// Returns the permissions of the segment (`p_flags`).
+    pub const fn flags(&self) -> u32 { self.flags }
+
+    /// Returns the offset of the segment in the file.
+    pub const fn offset(&self) -> u64 { self.offset }
+
+    /// Returns the virtual address of the segment in memory.
+    pub const fn virtual_address(&self) -> u64 { self.virtual_address }
+
+    /// Returns the physical address of the segment.
+    pub const fn physical_address(&self) -> u64 { self.physical_address }
+
+    /// Returns the number of bytes of the segment in the file.
+    pub const fn file_size(&self) -> u64 { self.file_size }
+
+    /// Returns the number of bytes of the segment in memory.
+    pub const fn memory_size(&self) -> u64 { self.memory_size }
+
+    /// Returns the alignment of the segment.
+    pub const fn align(&self) -> u64 { self.align }
+}
+
+/// An [`ElfFile`] reads the file header, the section headers and the program
+/// headers of an ELF file (executables, shared libraries and object files),
+/// that is located in a [`SubCursor`].
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{ElfFile, Endian, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// let mut data = vec![0; 64];
+/// data[..6].copy_from_slice(b"\x7fELF\x02\x01");
+///
+/// let elf = ElfFile::new(SubCursor::from(data))?;
+/// assert!(elf.is_64());
+/// assert_eq!(elf.endian(), Endian::Little);
+/// assert!(elf.sections().is_empty());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Note
+///
+/// 32-bit and 64-bit files with both byte orders are supported.
+#[derive(Debug, Clone)]
+pub struct ElfFile<T> {
+    sub_cursor: SubCursor<T>,
+    is_64: bool,
+    endian: Endian,
+    kind: u16,
+    machine: u16,
+    entry: u64,
+    sections: Vec<ElfSection>,
+    segments: Vec<ElfSegment>,
+}
+
+impl<T> ElfFile<T>
+where
+    T: Read + Seek,
+{
+    /// Reads the headers of the ELF file in the [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the magic or a header is invalid, if the
+    /// section names are out of bounds or if the [`SubCursor`] could not be
+    /// read.
+    pub fn new(sub_cursor: SubCursor<T>) -> io::Result<Self> {
+        let mut ident = [0; 16];
+        sub_cursor.read_exact_at(0, &mut ident)?;
+
+        if &ident[..4] != b"\x7fELF" {
+            return Err(invalid_data("invalid elf magic"));
+        }
+
+        let is_64 = match ident[4] {
+            1 => false,
+            2 => true,
+            _ => return Err(invalid_data("invalid elf class")),
+        };
+
+        let endian = match ident[5] {
+            1 => Endian::Little,
+            2 => Endian::Big,
+            _ => return Err(invalid_data("invalid elf byte order")),
+        };
+
+        let reader = Reader { endian, is_64 };
+
+        let mut header = vec![0; if is_64 { 64 } else { 52 }];
+        sub_cursor.read_exact_at(0, &mut header)?;
+
+        // the fields after the entry point are shifted by the size of the
+        // three addresses
+        let (entry, program_offset, section_offset) = {
+            if is_64 {
+                (
+                    reader.u64(&header[24..]),
+                    reader.u64(&header[32..]),
+                    reader.u64(&header[40..]),
+                )
+            } else {
+                (
+                    reader.u32(&header[24..]),
+                    reader.u32(&header[28..]),
+                    reader.u32(&header[32..]),
+                )
+            }
+        };
+        let fields = &header[if is_64 { 54 } else { 42 }..];
+
+        let program_entry_size = endian.u16(fields);
+        let mut program_count = u64::from(endian.u16(&fields[2..]));
+        let section_entry_size = endian.u16(&fields[4..]);
+        let mut section_count = u64::from(endian.u16(&fields[6..]));
+        let mut names_index = u64::from(endian.u16(&fields[8..]));
+
+        let section_header_size = if is_64 { 64 } else { 40 };
+
+        // the counts are stored in the first section header, if they do not
+        // fit in the file header
+        if section_offset != 0
+            && (section_count == 0 || names_index == 0xffff || program_count == 0xffff)
+        {
+            let first = read_table(
+                &sub_cursor,
+                section_offset,
+                1,
+                section_entry_size,
+                section_header_size,
+            )?;
+            let (_, first) = reader.section(&first[0]);
+
+            if section_count == 0 {
+                section_count = first.size;
+            }
+
+            if names_index == 0xffff {
+                names_index = u64::from(first.link);
+            }
+
+            if program_count == 0xffff {
+                program_count = u64::from(first.info);
+            }
+        }
+
+        let sections = read_table(
+            &sub_cursor,
+            section_offset,
+            section_count,
+            section_entry_size,
+            section_header_size,
+        )?
+        .iter()
+        .map(|entry| reader.section(entry))
+        .collect::<Vec<_>>();
+
+        let segments = read_table(
+            &sub_cursor,
+            program_offset,
+            program_count,
+            program_entry_size,
+            if is_64 { 56 } else { 32 },
+        )?
+        .iter()
+        .map(|entry| reader.segment(entry))
+        .collect::<Vec<_>>();
+
+        let sections = resolve_names(&sub_cursor, sections, names_index)?;
+
+        Ok(Self {
+            sub_cursor,
+            is_64,
+            endian,
+            kind: endian.u16(&header[16..]),
+            machine: endian.u16(&header[18..]),
+            entry,
+            sections,
+            segments,
+        })
+    }
+
+    /// Returns the bytes of `section` in the file as a [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the section is out of bounds. A section,
+    /// that does not occupy space in the file (like `.bss`), is empty.
+    pub fn section_data(&self, section: &ElfSection) -> io::Result<SubCursor<T>> {
+        let size = if section.kind == SHT_NOBITS {
+            0
+        } else {
+            section.size
+        };
+
+        self.sub_cursor
+            .child(section.offset, size)
+            .ok_or_else(|| invalid_data("the section is out of bounds"))
+    }
+
+    /// Returns the bytes of `segment` in the file as a [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the segment is out of bounds.
+    pub fn segment_data(&self, segment: &ElfSegment) -> io::Result<SubCursor<T>> {
+        self.sub_cursor
+            .child(segment.offset, segment.file_size)
+            .ok_or_else(|| invalid_data("the segment is out of bounds"))
+    }
+}
+
+impl<T> ElfFile<T> {
+    /// Returns `true` for a 64-bit file and `false` for a 32-bit file.
+    pub const fn is_64(&self) -> bool { self.is_64 }
+
+    /// Returns the byte order of the file.
+    pub const fn endian(&self) -> Endian { self.endian }
+
+    /// Returns the type of the file (`e_type`, like `ET_EXEC` or `ET_DYN`).
+    pub const fn kind(&self) -> u16 { self.kind }
+
+    /// Returns the architecture of the file (`e_machine`).
+    pub const fn machine(&self) -> u16 { self.machine }
+
+    /// Returns the address of the entry point.
+    pub const fn entry(&self) -> u64 { self.entry }
+
+    /// Returns all sections in the order of the section header table.
+    pub fn sections(&self) -> &[ElfSection] { &self.sections }
+
+    /// Returns the first section with the provided name.
+    pub fn section_by_name(&self, name: &str) -> Option<&ElfSection> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Returns all segments in the order of the program header table.
+    pub fn segments(&self) -> &[ElfSegment] { &self.segments }
+
+    /// Consumes this [`ElfFile`], returning the [`SubCursor`].
+    pub fn into_inner(self) -> SubCursor<T> { self.sub_cursor }
+}
+
+/// Reads the fields, whose size depends on the class of the file.
+#[derive(Debug, Clone, Copy)]
+struct Reader {
+    endian: Endian,
+    is_64: bool,
+}
+
+impl Reader {
+    fn u32(self, bytes: &[u8]) -> u64 { u64::from(self.endian.u32(bytes)) }
+
+    fn u64(self, bytes: &[u8]) -> u64 { self.endian.u64(bytes) }
+
+    /// Reads a field, that has the size of an address.
+    fn address(self, bytes: &[u8]) -> u64 {
+        if self.is_64 {
+            self.u64(bytes)
+        } else {
+            self.u32(bytes)
+        }
+    }
+
+    /// Parses a section header and returns the offset of its name in the
+    /// string table.
+    fn section(self, entry: &[u8]) -> (u32, ElfSection) {
+        let width = if self.is_64 { 8 } else { 4 };
+        let link = 8 + 4 * width;
+
+        let section = ElfSection {
+            name: String::new(),
+            kind: self.endian.u32(&entry[4..]),
+            flags: self.address(&entry[8..]),
+            address: self.address(&entry[8 + width..]),
+            offset: self.address(&entry[8 + 2 * width..]),
+            size: self.address(&entry[8 + 3 * width..]),
+            link: self.endian.u32(&entry[link..]),
+            info: self.endian.u32(&entry[link + 4..]),
+            align: self.address(&entry[link + 8..]),
+            entry_size: self.address(&entry[link + 8 + width..]),
+        };
+
+        (self.endian.u32(entry), section)
+    }
+
+    /// Parses a program header, the flags are moved in the 64-bit format.
+    fn segment(self, entry: &[u8]) -> ElfSegment {
+        if self.is_64 {
+            ElfSegment {
+                kind: self.endian.u32(entry),
+                flags: self.endian.u32(&entry[4..]),
+                offset: self.u64(&entry[8..]),
+                virtual_address: self.u64(&entry[16..]),
+                physical_address: self.u64(&entry[24..]),
+                file_size: self.u64(&entry[32..]),
+                memory_size: self.u64(&entry[40..]),
+                align: self.u64(&entry[48..]),
+            }
+        } else {
+            ElfSegment {
+                kind: self.endian.u32(entry),
+                offset: self.u32(&entry[4..]),
+                virtual_address: self.u32(&entry[8..]),
+                physical_address: self.u32(&entry[12..]),
+                file_size: self.u32(&entry[16..]),
+                memory_size: self.u32(&entry[20..]),
+                flags: self.endian.u32(&entry[24..]),
+                align: self.u32(&entry[28..]),
+            }
+        }
+    }
+}
+
+/// Reads a table of `count` headers, that are `entry_size` bytes long.
+fn read_table<T: Read + Seek>(
+    sub_cursor: &SubCursor<T>,
+    offset: u64,
+    count: u64,
+    entry_size: u16,
+    min_size: u16,
+) -> io::Result<Vec<Vec<u8>>> {
+    if count == 0 {
+        return Ok(vec![]);
+    }
+
+    if entry_size < min_size {
+        return Err(invalid_data("invalid size of a header table entry"));
+    }
+
+    let len = count
+        .checked_mul(u64::from(entry_size))
+        .filter(|len| *len <= sub_cursor.len() as u64)
+        .and_then(|len| usize::try_from(len).ok())
+        .ok_or_else(|| invalid_data("the header table is out of bounds"))?;
+
+    let mut table = vec![0; len];
+    sub_cursor.read_exact_at(offset, &mut table)?;
+
+    Ok(table
+        .chunks(usize::from(entry_size))
+        .map(<[u8]>::to_vec)
+        .collect())
+}
+
+/// Sets the names of the sections, which are offsets in the section header
+/// string table.
+fn resolve_names<T: Read + Seek>(
+    sub_cursor: &SubCursor<T>,
+    sections: Vec<(u32, ElfSection)>,
+    names_index: u64,
+) -> io::Result<Vec<ElfSection>> {
+    let names = match usize::try_from(names_index).map(|index| sections.get(index)) {
+        Ok(Some((_, names))) if names_index != 0 => {
+            let mut result = vec![];
+            sub_cursor
+                .child(names.offset, names.size)
+                .ok_or_else(|| invalid_data("the section names are out of bounds"))?
+                .read_to_end(&mut result)?;
+            result
+        }
+        _ => vec![],
+    };
+
+    Ok(sections
+        .into_iter()
+        .map(|(name_offset, mut section)| {
+            let name = usize::try_from(name_offset)
+                .ok()
+                .and_then(|offset| names.get(offset..))
+                .unwrap_or(&[]);
+            let end = name
+                .iter()
+                .position(|byte| *byte == 0)
+                .unwrap_or(name.len());
+
+            section.name = String::from_utf8_lossy(&name[..end]).into_owned();
+            section
+        })
+        .collect())
+}
+
+fn invalid_data(message: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, message) }
@@ -0,0 +1,99 @@
+/// The byte order of the integers in a file format.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::Endian;
+/// assert_eq!(Endian::Little.u32(&[1, 2, 0, 0]), 0x0201);
+/// assert_eq!(Endian::Big.u32(&[0, 0, 1, 2]), 0x0102);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endian {
+    /// The least significant byte comes first.
+    Little,
+    /// The most significant byte comes first.
+    Big,
+}
+
+impl Endian {
+    /// Reads an `u16` from the first 2 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than 2 bytes.
+    pub fn u16(self, bytes: &[u8]) -> u16 {
+        let mut buffer = [0; 2];
+        buffer.copy_from_slice(&bytes[..2]);
+
+        match self {
+            Self::Little => u16::from_le_bytes(buffer),
+            Self::Big => u16::from_be_bytes(buffer),
+        }
+    }
+
+    /// Reads an `u32` from the first 4 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than 4 bytes.
+    pub fn u32(self, bytes: &[u8]) -> u32 {
+        let mut buffer = [0; 4];
+        buffer.copy_from_slice(&bytes[..4]);
+
+        match self {
+            Self::Little => u32::from_le_bytes(buffer),
+            Self::Big => u32::from_be_bytes(buffer),
+        }
+    }
+
+    /// Reads an `u64` from the first 8 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than 8 bytes.
+    pub fn u64(self, bytes: &[u8]) -> u64 {
+        let mut buffer = [0; 8];
+        buffer.copy_from_slice(&bytes[..8]);
+
+        match self {
+            Self::Little => u64::from_le_bytes(buffer),
+            Self::Big => u64::from_be_bytes(buffer),
+        }
+    }
+
+    /// Writes `value` to the first 2 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than 2 bytes.
+    pub fn write_u16(self, bytes: &mut [u8], value: u16) {
+        bytes[..2].copy_from_slice(&match self {
+            Self::Little => value.to_le_bytes(),
+            Self::Big => value.to_be_bytes(),
+        });
+    }
+
+    /// Writes `value` to the first 4 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than 4 bytes.
+    pub fn write_u32(self, bytes: &mut [u8], value: u32) {
+        bytes[..4].copy_from_slice(&match self {
+            Self::Little => value.to_le_bytes(),
+            Self::Big => value.to_be_bytes(),
+        });
+    }
+
+    /// Writes `value` to the first 8 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than 8 bytes.
+    pub fn write_u64(self, bytes: &mut [u8], value: u64) {
+        bytes[..8].copy_from_slice(&match self {
+            Self::Little => value.to_le_bytes(),
+            Self::Big => value.to_be_bytes(),
+        });
+    }
+}
@@ -79,9 +79,106 @@
 //! [`Write`]: std::io::Write
 //! [`Read`]: std::io::Read
 //! [`Seek`]: std::io::Seek
+#[cfg(feature = "ar")]
+mod ar;
+#[cfg(feature = "bmff")]
+mod bmff;
+mod chain_cursor;
+mod chunks;
+mod coverage_tracker;
+#[cfg(any(feature = "disk", feature = "png"))]
+mod crc32;
+mod dirty_tracker;
+#[cfg(feature = "disk")]
+mod disk;
+#[cfg(feature = "elf")]
+mod elf;
+#[cfg(any(
+    feature = "bmff",
+    feature = "disk",
+    feature = "elf",
+    feature = "pe",
+    feature = "png",
+    feature = "riff",
+    feature = "tiff",
+    feature = "zip"
+))]
+mod endian;
+mod line_index;
+mod multi_volume;
+mod overlay;
+mod partition;
+mod patches;
+#[cfg(feature = "pe")]
+mod pe;
+#[cfg(feature = "png")]
+mod png;
 pub mod prelude;
+mod range_set;
+mod recorder;
+mod records;
+mod registry;
+mod reverse;
+#[cfg(feature = "riff")]
+mod riff;
+mod search;
+mod split;
 mod sub_cursor;
+#[cfg(feature = "tar")]
+mod tar;
+#[cfg(feature = "tiff")]
+mod tiff;
+mod transaction;
+mod utils;
+#[cfg(feature = "zip")]
+mod zip;
 
+#[cfg(feature = "ar")]
+pub use crate::ar::{ArArchive, ArEntry};
+#[cfg(feature = "bmff")]
+pub use crate::bmff::{IsoBox, IsoBoxes};
+pub use crate::chain_cursor::ChainCursor;
+pub use crate::chunks::{Chunks, ChunksExact};
+pub use crate::coverage_tracker::CoverageTracker;
+pub use crate::dirty_tracker::DirtyTracker;
+#[cfg(feature = "disk")]
+pub use crate::disk::{Partition, PartitionTable};
+#[cfg(feature = "elf")]
+pub use crate::elf::{ElfFile, ElfSection, ElfSegment};
+#[cfg(any(
+    feature = "bmff",
+    feature = "disk",
+    feature = "elf",
+    feature = "pe",
+    feature = "png",
+    feature = "riff",
+    feature = "tiff",
+    feature = "zip"
+))]
+pub use crate::endian::Endian;
+pub use crate::line_index::LineIndex;
+pub use crate::multi_volume::MultiVolume;
+pub use crate::overlay::Overlay;
+pub use crate::partition::PositionalFile;
+#[cfg(feature = "pe")]
+pub use crate::pe::{DataDirectory, PeFile, PeSection};
+#[cfg(feature = "png")]
+pub use crate::png::{PngChunk, PngChunks};
+pub use crate::recorder::{Event, Recorder, Replay};
+pub use crate::records::{Decode, Encode, RecordCursor, Records};
+pub use crate::registry::Registry;
+pub use crate::reverse::{RLines, RSplit, ReverseReader};
+#[cfg(feature = "riff")]
+pub use crate::riff::{RiffChunk, RiffChunks};
+pub use crate::search::FindIter;
+pub use crate::split::{Lines, Split};
 pub use crate::sub_cursor::*;
+#[cfg(feature = "tar")]
+pub use crate::tar::{EntryType, TarArchive, TarEntry, TarHeader, TarWriter};
+#[cfg(feature = "tiff")]
+pub use crate::tiff::{Ifd, IfdEntry, TiffFile};
+pub use crate::transaction::Transaction;
+#[cfg(feature = "zip")]
+pub use crate::zip::{CompressionMethod, ZipArchive, ZipEntry, ZipReader};
 
 //
@@ -0,0 +1,439 @@
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::utils::{read_varint, write_varint};
+use crate::SubCursor;
+
+const MAGIC: &[u8; 6] = b"SCIDX\x01";
+/// The maximum length of a delimiter in a saved index.
+const MAX_DELIMITER_LEN: usize = 1024;
+
+/// A [`LineIndex`] allows random access to the lines (or records) of a huge
+/// [`SubCursor`], without scanning it again.
+///
+/// The offset of every `interval`-th line is stored, so finding a line only
+/// requires a single seek and scanning at most `interval` lines.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{LineIndex, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::Read;
+///
+/// let sub_cursor = SubCursor::from(b"zero\none\ntwo\nthree\n".to_vec());
+/// let index = LineIndex::build(&sub_cursor, 2)?;
+///
+/// assert_eq!(index.len(), 4);
+///
+/// let mut line = index.line(&sub_cursor, 3)?;
+/// let mut buffer = String::new();
+/// line.read_to_string(&mut buffer)?;
+/// assert_eq!(buffer, "three".to_string());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    interval: usize,
+    delimiter: Vec<u8>,
+    source_len: u64,
+    // the modification time of the source file (since the unix epoch)
+    modified: Option<Duration>,
+    lines: u64,
+    // the offset of every `interval`-th line
+    checkpoints: Vec<u64>,
+}
+
+impl LineIndex {
+    /// Scans the [`SubCursor`] and remembers the offset of every
+    /// `interval`-th line. The lines are split like [`SubCursor::lines`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the [`SubCursor`] could not be read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is 0.
+    ///
+    /// [`SubCursor::lines`]: crate::SubCursor::lines
+    pub fn build<T: Read + Seek>(sub_cursor: &SubCursor<T>, interval: usize) -> io::Result<Self> {
+        Self::build_with_delimiter(sub_cursor, b"\n", interval)
+    }
+
+    /// Scans the [`SubCursor`] and remembers the offset of every
+    /// `interval`-th record, the records are separated by `delimiter` like
+    /// with [`SubCursor::split`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{LineIndex, SubCursor};
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let sub_cursor = SubCursor::from(b"a\0b\0c".to_vec());
+    /// let index = LineIndex::build_with_delimiter(&sub_cursor, b"\0", 16)?;
+    ///
+    /// assert_eq!(index.len(), 3);
+    /// assert_eq!(index.line(&sub_cursor, 2)?.get_start(), 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// A `\n` delimiter is treated like [`build`], so `\r\n` line endings
+    /// are supported.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the [`SubCursor`] could not be read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is 0 or if the `delimiter` is empty.
+    ///
+    /// [`build`]: #method.build
+    /// [`SubCursor::split`]: crate::SubCursor::split
+    pub fn build_with_delimiter<T: Read + Seek>(
+        sub_cursor: &SubCursor<T>,
+        delimiter: &[u8],
+        interval: usize,
+    ) -> io::Result<Self> {
+        assert_ne!(interval, 0, "the interval must not be 0");
+        assert!(!delimiter.is_empty(), "the delimiter must not be empty");
+
+        let mut result = Self {
+            interval,
+            delimiter: delimiter.to_vec(),
+            source_len: sub_cursor.len() as u64,
+            modified: None,
+            lines: 0,
+            checkpoints: vec![],
+        };
+
+        let is_lines = result.is_lines();
+
+        // only the offsets of the delimiters are needed, so the matches are
+        // used directly instead of creating a `SubCursor` for every record
+        let mut insert = |offset: usize| {
+            if result.lines.is_multiple_of(interval as u64) {
+                result.checkpoints.push(offset as u64);
+            }
+
+            result.lines += 1;
+        };
+
+        let mut offset = 0;
+        for found in sub_cursor.find_iter(delimiter) {
+            insert(offset);
+            offset = found? + delimiter.len();
+        }
+
+        // like `lines`, there is no empty line after a final newline
+        if offset < sub_cursor.len() || !is_lines {
+            insert(offset);
+        }
+
+        Ok(result)
+    }
+
+    /// Opens the index for the file at `source`, that is stored at `sidecar`.
+    /// The index will be built and saved, if the `sidecar` does not exist or
+    /// if it is outdated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{LineIndex, SubCursor};
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::fs::{self, File};
+    ///
+    /// # let directory = std::env::temp_dir()
+    /// #     .join(format!("sub_cursor_line_index_doc_{}", std::process::id()));
+    /// # fs::create_dir_all(&directory)?;
+    /// let source = directory.join("server.log");
+    /// fs::write(&source, b"first\nsecond\nthird\n")?;
+    ///
+    /// let index = LineIndex::open(&source, directory.join("server.log.idx"), 1024)?;
+    /// let sub_cursor = SubCursor::from(File::open(&source)?);
+    ///
+    /// assert_eq!(index.line(&sub_cursor, 1)?.get_start(), 6);
+    /// # fs::remove_dir_all(&directory)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// An index with a different `interval` will be rebuilt as well.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the `source` could not be read or if the
+    /// index could not be saved.
+    pub fn open<P, Q>(source: P, sidecar: Q, interval: usize) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let metadata = fs::metadata(source.as_ref())?;
+
+        if let Ok(file) = File::open(sidecar.as_ref()) {
+            if let Ok(index) = Self::load(BufReader::new(file)) {
+                if index.interval == interval && index.is_lines() && index.is_valid(&metadata) {
+                    return Ok(index);
+                }
+            }
+        }
+
+        let sub_cursor = SubCursor::from(File::open(source.as_ref())?);
+        let mut index = Self::build(&sub_cursor, interval)?;
+        index.modified = modified(&metadata);
+
+        index.save(BufWriter::new(File::create(sidecar.as_ref())?))?;
+        Ok(index)
+    }
+
+    /// Returns the number of lines.
+    pub const fn len(&self) -> u64 { self.lines }
+
+    /// Returns `true`, if there are no lines.
+    pub const fn is_empty(&self) -> bool { self.lines == 0 }
+
+    /// Returns `true`, if the index still matches the file with the provided
+    /// metadata, by comparing the length and the modification time.
+    pub fn is_valid(&self, metadata: &fs::Metadata) -> bool {
+        self.source_len == metadata.len() && self.modified == modified(metadata)
+    }
+
+    /// Returns the line with the index `line` (starting at `0`).
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the index does not belong to the
+    /// [`SubCursor`] (the length is different), if `line` is out of bounds or
+    /// if the [`SubCursor`] could not be read.
+    pub fn line<T: Read + Seek>(
+        &self,
+        sub_cursor: &SubCursor<T>,
+        line: u64,
+    ) -> io::Result<SubCursor<T>> {
+        if sub_cursor.len() as u64 != self.source_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the index does not belong to the SubCursor",
+            ));
+        }
+
+        if line >= self.lines {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("line {} is out of bounds (lines: {})", line, self.lines),
+            ));
+        }
+
+        let interval = self.interval as u64;
+        let offset = usize::try_from(line / interval)
+            .ok()
+            .and_then(|index| self.checkpoints.get(index).copied())
+            .ok_or_else(|| invalid_index("invalid checkpoint"))?;
+        // the remainder is smaller than the interval, which is an `usize`
+        let skip = usize::try_from(line % interval).unwrap_or(usize::MAX);
+
+        let rest = self
+            .source_len
+            .checked_sub(offset)
+            .and_then(|len| sub_cursor.child(offset, len))
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+
+        let record = {
+            if self.is_lines() {
+                rest.lines().nth(skip)
+            } else {
+                rest.split(&self.delimiter).nth(skip)
+            }
+        };
+
+        record.unwrap_or_else(|| Err(io::ErrorKind::UnexpectedEof.into()))
+    }
+
+    /// Returns the lines in `range` as a single [`SubCursor`], including the
+    /// delimiters between them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{LineIndex, SubCursor};
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::Read;
+    ///
+    /// let sub_cursor = SubCursor::from(b"zero\none\ntwo\nthree\n".to_vec());
+    /// let index = LineIndex::build(&sub_cursor, 2)?;
+    ///
+    /// let mut lines = index.lines(&sub_cursor, 1..3)?;
+    /// let mut buffer = String::new();
+    /// lines.read_to_string(&mut buffer)?;
+    /// assert_eq!(buffer, "one\ntwo".to_string());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the `range` is empty or for the same
+    /// reasons as [`line`].
+    ///
+    /// [`line`]: #method.line
+    pub fn lines<T: Read + Seek>(
+        &self,
+        sub_cursor: &SubCursor<T>,
+        range: Range<u64>,
+    ) -> io::Result<SubCursor<T>> {
+        if range.start >= range.end {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the range of lines must not be empty",
+            ));
+        }
+
+        let first = self.line(sub_cursor, range.start)?;
+        let last = self.line(sub_cursor, range.end - 1)?;
+
+        Ok(first.end(last.get_end()))
+    }
+
+    /// Writes the index to `writer`.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the index could not be written.
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+
+        write_varint(&mut writer, self.interval as u64)?;
+        write_varint(&mut writer, self.delimiter.len() as u64)?;
+        writer.write_all(&self.delimiter)?;
+        write_varint(&mut writer, self.source_len)?;
+
+        if let Some(modified) = self.modified {
+            writer.write_all(&[1])?;
+            write_varint(&mut writer, modified.as_secs())?;
+            write_varint(&mut writer, u64::from(modified.subsec_nanos()))?;
+        } else {
+            writer.write_all(&[0])?;
+        }
+
+        write_varint(&mut writer, self.lines)?;
+        write_varint(&mut writer, self.checkpoints.len() as u64)?;
+
+        // the checkpoints are ascending, so only the difference is stored
+        let mut previous = 0;
+        for checkpoint in &self.checkpoints {
+            write_varint(&mut writer, checkpoint - previous)?;
+            previous = *checkpoint;
+        }
+
+        writer.flush()
+    }
+
+    /// Reads an index, that has been written with [`save`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the data is not a valid index.
+    ///
+    /// [`save`]: #method.save
+    pub fn load<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; 6];
+        reader.read_exact(&mut magic)?;
+
+        if &magic != MAGIC {
+            return Err(invalid_index("invalid index header"));
+        }
+
+        let interval = usize::try_from(read_varint(&mut reader)?)
+            .map_err(|_| invalid_index("the interval of the index is too large"))?;
+        if interval == 0 {
+            return Err(invalid_index("the interval of the index is 0"));
+        }
+
+        let delimiter_len = usize::try_from(read_varint(&mut reader)?)
+            .ok()
+            .filter(|len| *len != 0 && *len <= MAX_DELIMITER_LEN)
+            .ok_or_else(|| invalid_index("invalid length of the delimiter"))?;
+
+        let mut delimiter = vec![0; delimiter_len];
+        reader.read_exact(&mut delimiter)?;
+        let source_len = read_varint(&mut reader)?;
+
+        let mut flag = [0; 1];
+        reader.read_exact(&mut flag)?;
+        let modified = {
+            if flag[0] == 0 {
+                None
+            } else {
+                let seconds = read_varint(&mut reader)?;
+                let nanos = u32::try_from(read_varint(&mut reader)?)
+                    .ok()
+                    .filter(|nanos| *nanos < 1_000_000_000)
+                    .ok_or_else(|| invalid_index("invalid modification time"))?;
+
+                Some(Duration::new(seconds, nanos))
+            }
+        };
+
+        let lines = read_varint(&mut reader)?;
+        let count = read_varint(&mut reader)?;
+
+        let expected = lines
+            .checked_add(interval as u64 - 1)
+            .map(|lines| lines / interval as u64);
+        if expected != Some(count) {
+            return Err(invalid_index(
+                "the number of checkpoints does not match the number of lines",
+            ));
+        }
+
+        let mut checkpoints = vec![];
+        let mut previous = 0_u64;
+        for index in 0..count {
+            let difference = read_varint(&mut reader)?;
+
+            // the checkpoints are strictly ascending and inside of the source
+            previous = previous
+                .checked_add(difference)
+                .filter(|checkpoint| (index == 0 || difference > 0) && *checkpoint <= source_len)
+                .ok_or_else(|| invalid_index("invalid checkpoint"))?;
+            checkpoints.push(previous);
+        }
+
+        Ok(Self {
+            interval,
+            delimiter,
+            source_len,
+            modified,
+            lines,
+            checkpoints,
+        })
+    }
+
+    fn is_lines(&self) -> bool { self.delimiter == b"\n" }
+}
+
+fn invalid_index(message: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, message) }
+
+fn modified(metadata: &fs::Metadata) -> Option<Duration> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+}
@@ -0,0 +1,250 @@
+use std::cmp;
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::utils::seek_position;
+
+/// A [`MultiVolume`] presents a sequence of files (volumes) as one continuous
+/// stream, for example a split archive (`archive.001`, `archive.002`, ...).
+///
+/// The volumes are only opened, when they are read, and only the volume, that
+/// is currently read, is kept open.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{MultiVolume, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::fs;
+/// use std::io::Read;
+///
+/// # let directory = std::env::temp_dir()
+/// #     .join(format!("sub_cursor_multi_volume_doc_{}", std::process::id()));
+/// # fs::create_dir_all(&directory)?;
+/// fs::write(directory.join("archive.001"), b"Hello ")?;
+/// fs::write(directory.join("archive.002"), b"World!")?;
+///
+/// let multi_volume = MultiVolume::open(directory.join("archive.001"))?;
+/// assert_eq!(multi_volume.len(), 12);
+///
+/// // the entry straddles both volumes
+/// let mut sub_cursor = SubCursor::from(multi_volume).start(3).end(9);
+///
+/// let mut result = String::new();
+/// sub_cursor.read_to_string(&mut result)?;
+/// assert_eq!(result, "lo Wor".to_string());
+/// # fs::remove_dir_all(&directory)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MultiVolume {
+    volumes: Vec<Volume>,
+    len: u64,
+    position: u64,
+    // the index and the file of the volume, that is currently open
+    current: Option<(usize, File)>,
+}
+
+#[derive(Debug)]
+struct Volume {
+    path: PathBuf,
+    // the offset of the volume in the stream
+    offset: u64,
+    len: u64,
+}
+
+impl MultiVolume {
+    /// Creates a new [`MultiVolume`] from the provided paths, the volumes will
+    /// be read in the provided order.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the size of a volume can not be
+    /// determined.
+    pub fn new<I, P>(paths: I) -> io::Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        let mut volumes = vec![];
+        let mut len = 0;
+
+        for path in paths {
+            let path = path.into();
+            let size = fs::metadata(&path)?.len();
+
+            volumes.push(Volume {
+                path,
+                offset: len,
+                len: size,
+            });
+
+            len += size;
+        }
+
+        Ok(Self {
+            volumes,
+            len,
+            position: 0,
+            current: None,
+        })
+    }
+
+    /// Creates a new [`MultiVolume`], that starts with the provided volume.
+    ///
+    /// The extension of `first` has to be a number (like `001`), the following
+    /// volumes will be searched by incrementing this number (`002`, `003`,
+    /// ...), until there is no file with that name.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the extension of `first` is not a number
+    /// or if the size of a volume can not be determined.
+    pub fn open<P: AsRef<Path>>(first: P) -> io::Result<Self> {
+        let first = first.as_ref();
+
+        let extension = first
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .filter(|extension| !extension.is_empty())
+            .filter(|extension| extension.bytes().all(|byte| byte.is_ascii_digit()))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the extension of the first volume has to be a number",
+                )
+            })?;
+
+        let width = extension.len();
+        let start = extension
+            .parse::<u64>()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error.to_string()))?;
+
+        let mut paths = vec![first.to_path_buf()];
+        for number in start + 1.. {
+            let path = first.with_extension(format!("{number:0width$}"));
+
+            if !path.is_file() {
+                break;
+            }
+
+            paths.push(path);
+        }
+
+        Self::new(paths)
+    }
+
+    /// Returns the combined length of all volumes.
+    pub const fn len(&self) -> u64 { self.len }
+
+    /// Returns `true`, if the combined length of all volumes is `0`.
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns the paths of all volumes.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.volumes.iter().map(|volume| volume.path.as_path())
+    }
+
+    /// Returns the index of the volume, that contains `position`.
+    fn locate(&self, position: u64) -> Option<usize> {
+        if position >= self.len {
+            return None;
+        }
+
+        Some(
+            self.volumes
+                .partition_point(|volume| volume.offset <= position)
+                - 1,
+        )
+    }
+}
+
+impl Seek for MultiVolume {
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        self.position = seek_position(self.position, self.len, style)?;
+        Ok(self.position)
+    }
+}
+
+impl Read for MultiVolume {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut result = 0;
+
+        while result < buf.len() {
+            let Some(index) = self.locate(self.position) else {
+                break;
+            };
+
+            let position = self.position;
+            let volume = &self.volumes[index];
+            let offset = position - volume.offset;
+
+            // the previous volume is closed, when the next one is opened
+            let file = match &mut self.current {
+                Some((current, file)) if *current == index => file,
+                current => &mut current.insert((index, File::open(&volume.path)?)).1,
+            };
+
+            let available = usize::try_from(volume.len - offset).unwrap_or(usize::MAX);
+            let remaining = cmp::min(buf.len() - result, available);
+
+            file.seek(SeekFrom::Start(offset))?;
+            let read = file.read(&mut buf[result..result + remaining])?;
+
+            // the volume is shorter than expected
+            if read == 0 {
+                break;
+            }
+
+            result += read;
+            self.position += read as u64;
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_current_volume() -> io::Result<()> {
+        let directory = std::env::temp_dir().join(format!(
+            "sub_cursor_test_current_volume_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&directory)?;
+        fs::write(directory.join("data.1"), b"abc")?;
+        fs::write(directory.join("data.2"), b"def")?;
+
+        let mut multi_volume = MultiVolume::open(directory.join("data.1"))?;
+        assert!(multi_volume.current.is_none());
+
+        let mut buffer = [0; 4];
+        multi_volume.read_exact(&mut buffer)?;
+        assert_eq!(&buffer, b"abcd");
+        // only the second volume is still open
+        assert_eq!(
+            multi_volume.current.as_ref().map(|(index, _)| *index),
+            Some(1)
+        );
+
+        multi_volume.seek(SeekFrom::Start(1))?;
+        multi_volume.read_exact(&mut buffer[..1])?;
+        assert_eq!(buffer[0], b'b');
+        assert_eq!(
+            multi_volume.current.as_ref().map(|(index, _)| *index),
+            Some(0)
+        );
+
+        fs::remove_dir_all(&directory)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+use std::cmp;
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+use crate::patches::Patches;
+use crate::SubCursor;
+
+/// An [`Overlay`] is a copy-on-write view of a [`SubCursor`].
+///
+/// All writes will be stored in a private patch map, instead of the underlying
+/// stream, and reads will see the patched data. The changes can be
+/// [`commit`]ted to the underlying stream or [`discard`]ed.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::SubCursor;
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+/// use std::sync::{Arc, Mutex};
+///
+/// let cursor = Arc::new(Mutex::new(Cursor::new(b"Hello World!".to_vec())));
+/// let mut overlay = SubCursor::from(cursor.clone()).end(12).overlay();
+///
+/// overlay.seek(SeekFrom::Start(6))?;
+/// overlay.write_all(b"Rust!")?;
+///
+/// // the underlying stream has not been changed
+/// assert_eq!(cursor.lock().unwrap().get_ref(), b"Hello World!");
+///
+/// // but reading from the overlay returns the patched data
+/// let mut result = String::new();
+/// overlay.seek(SeekFrom::Start(0))?;
+/// overlay.read_to_string(&mut result)?;
+/// assert_eq!(result, "Hello Rust!!".to_string());
+///
+/// // write the changes to the underlying stream
+/// overlay.commit()?;
+/// assert_eq!(cursor.lock().unwrap().get_ref(), b"Hello Rust!!");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`commit`]: #method.commit
+/// [`discard`]: #method.discard
+#[derive(Default, Debug, Clone)]
+pub struct Overlay<T> {
+    sub_cursor: SubCursor<T>,
+    // the offsets are relative to the start of the SubCursor
+    patches: Patches,
+}
+
+impl<T> SubCursor<T> {
+    /// Creates a new [`Overlay`] over this [`SubCursor`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// let overlay = SubCursor::from(vec![1, 2, 3, 4]).overlay();
+    ///
+    /// assert!(!overlay.is_dirty());
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// The position of the [`Overlay`] will be set to the [`start`].
+    ///
+    /// [`Overlay`]: crate::Overlay
+    /// [`start`]: #method.start
+    pub fn overlay(&self) -> Overlay<T> {
+        Overlay {
+            sub_cursor: self.sub_cursor(),
+            patches: Patches::new(),
+        }
+    }
+}
+
+impl<T> Overlay<T> {
+    /// Returns all ranges (relative to the start of the [`SubCursor`]), that
+    /// have been modified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::{Seek, SeekFrom, Write};
+    ///
+    /// let mut overlay = SubCursor::from(vec![0; 20]).overlay();
+    ///
+    /// overlay.write_all(&[1, 2])?;
+    /// overlay.seek(SeekFrom::Start(10))?;
+    /// overlay.write_all(&[3, 4, 5])?;
+    /// overlay.seek(SeekFrom::Start(2))?;
+    /// overlay.write_all(&[6])?;
+    ///
+    /// assert_eq!(overlay.dirty_ranges(), vec![0..3, 10..13]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    pub fn dirty_ranges(&self) -> Vec<Range<u64>> { self.patches.ranges() }
+
+    /// Returns `true`, if there are any changes, that have not been committed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::Write;
+    ///
+    /// let mut overlay = SubCursor::from(vec![0; 20]).overlay();
+    /// assert!(!overlay.is_dirty());
+    ///
+    /// overlay.write_all(&[1, 2])?;
+    /// assert!(overlay.is_dirty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_dirty(&self) -> bool { !self.patches.is_empty() }
+
+    /// Throws away all changes, that have not been committed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::{Read, Seek, SeekFrom, Write};
+    ///
+    /// let mut overlay = SubCursor::from(vec![0; 4]).overlay();
+    ///
+    /// overlay.write_all(&[1, 2])?;
+    /// overlay.discard();
+    ///
+    /// let mut result = vec![];
+    /// overlay.seek(SeekFrom::Start(0))?;
+    /// overlay.read_to_end(&mut result)?;
+    /// assert_eq!(result, vec![0; 4]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn discard(&mut self) { self.patches.clear(); }
+
+    /// Consumes the [`Overlay`] and returns the underlying [`SubCursor`].
+    /// All changes, that have not been committed, will be lost.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// let overlay = SubCursor::from(vec![1, 2, 3]).overlay();
+    /// let sub_cursor = overlay.into_inner();
+    ///
+    /// assert_eq!(sub_cursor.len(), 3);
+    /// ```
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    pub fn into_inner(self) -> SubCursor<T> { self.sub_cursor }
+}
+
+impl<T> Overlay<T>
+where
+    T: Write + Seek,
+{
+    /// Writes all changes to the underlying stream.
+    ///
+    /// The underlying stream will be locked for the entire commit, so other
+    /// [`SubCursor`]s will either see none or all of the changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::{Cursor, Write};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let cursor = Arc::new(Mutex::new(Cursor::new(vec![0; 6])));
+    /// let mut overlay = SubCursor::from(cursor.clone()).start(2).end(6).overlay();
+    ///
+    /// overlay.write_all(&[1, 2])?;
+    /// overlay.commit()?;
+    ///
+    /// assert!(!overlay.is_dirty());
+    /// assert_eq!(cursor.lock().unwrap().get_ref(), &[0, 0, 1, 2, 0, 0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the underlying stream could not be
+    /// written.
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    pub fn commit(&mut self) -> io::Result<()> {
+        let patches = &self.patches;
+
+        self.sub_cursor.with_inner(|cursor, start| {
+            for (offset, bytes) in patches.iter() {
+                cursor.seek(SeekFrom::Start(start + offset))?;
+                cursor.write_all(bytes)?;
+            }
+
+            Ok(())
+        })?;
+
+        self.patches.clear();
+        Ok(())
+    }
+}
+
+impl<T> Seek for Overlay<T>
+where
+    T: Seek,
+{
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> { self.sub_cursor.seek(style) }
+}
+
+impl<T> Read for Overlay<T>
+where
+    T: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.sub_cursor.sync();
+        let offset = self.sub_cursor.position();
+        let result = self.sub_cursor.read(buf)?;
+
+        self.patches.apply(offset, &mut buf[..result]);
+
+        Ok(result)
+    }
+}
+
+impl<T> Write for Overlay<T>
+where
+    T: Seek,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.sub_cursor.sync();
+        let offset = self.sub_cursor.position();
+        let remaining = (self.sub_cursor.len() as u64).saturating_sub(offset);
+        let result = cmp::min(buf.len(), usize::try_from(remaining).unwrap_or(usize::MAX));
+
+        if result == 0 {
+            return Ok(0);
+        }
+
+        self.patches.insert(offset, &buf[..result]);
+        // a slice is never longer than `isize::MAX` bytes
+        let len = i64::try_from(result).unwrap_or(i64::MAX);
+        self.sub_cursor.seek(SeekFrom::Current(len))?;
+
+        Ok(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
@@ -0,0 +1,344 @@
+use std::cmp;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::panic;
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::thread;
+
+use crate::search::CHUNK_SIZE;
+use crate::utils::seek_position;
+use crate::SubCursor;
+
+impl<T> SubCursor<T>
+where
+    T: Read + Seek,
+{
+    /// Divides the [`SubCursor`] into up to `count` roughly equal parts.
+    /// Every boundary is moved forward until it is directly after a
+    /// `delimiter`, so every part only contains whole records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let sub_cursor = SubCursor::from(b"a,b\nccc,d\ne,f\ng,h\n".to_vec());
+    /// let parts = sub_cursor.partition(2, b"\n")?;
+    ///
+    /// assert_eq!(
+    ///     parts
+    ///         .iter()
+    ///         .map(|part| (part.get_start(), part.get_end()))
+    ///         .collect::<Vec<_>>(),
+    ///     vec![(0, 10), (10, 18)]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// Empty parts are omitted, so there might be less than `count` parts
+    /// (for example if a record is larger than a part).
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the [`SubCursor`] could not be read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is 0 or if the `delimiter` is empty.
+    pub fn partition(&self, count: usize, delimiter: &[u8]) -> io::Result<Vec<Self>> {
+        assert_ne!(count, 0, "the number of parts must not be 0");
+        assert!(!delimiter.is_empty(), "the delimiter must not be empty");
+
+        let len = self.len();
+        let mut result = Vec::with_capacity(count);
+        let mut previous = 0;
+
+        for index in 1..=count {
+            let target = {
+                if index == count {
+                    len
+                } else {
+                    (len as u128 * index as u128 / count as u128) as usize
+                }
+            };
+
+            let boundary = {
+                if target <= previous {
+                    previous
+                } else if target == len {
+                    len
+                } else {
+                    // a delimiter, that ends exactly at the target, is found
+                    // as well
+                    let offset = cmp::max(target.saturating_sub(delimiter.len()), previous);
+
+                    self.find_from(offset, delimiter, CHUNK_SIZE)?
+                        .map_or(len, |found| found + delimiter.len())
+                }
+            };
+
+            if boundary > previous {
+                if let Some(part) = self.child(previous as u64, (boundary - previous) as u64) {
+                    result.push(part);
+                }
+
+                previous = boundary;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl SubCursor<PositionalFile> {
+    /// Divides the [`SubCursor`] like [`partition`] and calls `f` for every
+    /// part on a pool of `threads` threads. The results are returned in the
+    /// order of the parts.
+    ///
+    /// Every part gets its own clone of the [`PositionalFile`], which only
+    /// shares the file handle and reads with positional reads, so the threads
+    /// do not have to wait for each other.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{PositionalFile, SubCursor};
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::fs::{self, File};
+    /// use std::io::Read;
+    ///
+    /// # let directory = std::env::temp_dir()
+    /// #     .join(format!("sub_cursor_partition_doc_{}", std::process::id()));
+    /// # fs::create_dir_all(&directory)?;
+    /// let path = directory.join("data.csv");
+    /// fs::write(&path, b"1,2\n3,4\n5,6\n7,8\n")?;
+    ///
+    /// let sub_cursor = SubCursor::from(PositionalFile::new(File::open(&path)?)?);
+    /// let records = sub_cursor.par_partitions(4, b"\n", 2, |mut part| {
+    ///     let mut buffer = String::new();
+    ///     part.read_to_string(&mut buffer)?;
+    ///     Ok(buffer.lines().count())
+    /// })?;
+    ///
+    /// assert_eq!(records.iter().sum::<usize>(), 4);
+    /// # fs::remove_dir_all(&directory)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the [`SubCursor`] could not be
+    /// partitioned or if `f` returned an error for any part.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` or `threads` is 0, if the `delimiter` is empty or if
+    /// `f` panicked.
+    ///
+    /// [`partition`]: #method.partition
+    pub fn par_partitions<F, R>(
+        &self,
+        count: usize,
+        delimiter: &[u8],
+        threads: usize,
+        f: F,
+    ) -> io::Result<Vec<R>>
+    where
+        F: Fn(Self) -> io::Result<R> + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        assert_ne!(threads, 0, "the number of threads must not be 0");
+
+        let inner = self
+            .sub_cursor()
+            .into_inner()
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let mut parts = self
+            .partition(count, delimiter)?
+            .into_iter()
+            .map(|part| {
+                // every part has its own handle, so there is no shared lock
+                Self::from(inner.clone())
+                    .start(part.get_start())
+                    .end(part.get_end())
+            })
+            .enumerate()
+            .collect::<Vec<_>>();
+
+        let len = parts.len();
+        // the parts are taken from the back of the queue
+        parts.reverse();
+
+        let queue = Arc::new(Mutex::new(parts));
+        let f = Arc::new(f);
+        let (sender, receiver) = mpsc::channel();
+
+        let handles = (0..cmp::min(threads, len))
+            .map(|_| {
+                let queue = queue.clone();
+                let f = f.clone();
+                let sender = sender.clone();
+
+                thread::spawn(move || {
+                    loop {
+                        let job = queue.lock().unwrap_or_else(PoisonError::into_inner).pop();
+
+                        match job {
+                            Some((index, part)) => {
+                                if sender.send((index, f(part))).is_err() {
+                                    break;
+                                }
+                            }
+                            None => break,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        drop(sender);
+
+        let mut results = (0..len).map(|_| None).collect::<Vec<_>>();
+        for (index, result) in receiver {
+            results[index] = Some(result);
+        }
+
+        for handle in handles {
+            if let Err(error) = handle.join() {
+                panic::resume_unwind(error);
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.expect("every part has been processed"))
+            .collect()
+    }
+}
+
+/// A [`PositionalFile`] reads from a [`File`] with positional reads (`pread`
+/// on unix), so it does not change the position of the [`File`].
+///
+/// Cloning a [`PositionalFile`] is cheap and the clones have independent
+/// positions, so many threads can read the same [`File`] at once.
+///
+/// On platforms without positional reads, the clones share a lock and seek
+/// the [`File`] before every read, so the reads are still correct, but they
+/// can not happen at the same time.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::PositionalFile;
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::fs::{self, File};
+/// use std::io::{Read, Seek, SeekFrom};
+///
+/// # let directory = std::env::temp_dir()
+/// #     .join(format!("sub_cursor_positional_file_doc_{}", std::process::id()));
+/// # fs::create_dir_all(&directory)?;
+/// let path = directory.join("data");
+/// fs::write(&path, b"Hello World!")?;
+///
+/// let mut first = PositionalFile::new(File::open(&path)?)?;
+/// let mut second = first.clone();
+///
+/// second.seek(SeekFrom::Start(6))?;
+///
+/// let mut buffer = [0; 5];
+/// first.read_exact(&mut buffer)?;
+/// assert_eq!(&buffer, b"Hello");
+///
+/// second.read_exact(&mut buffer)?;
+/// assert_eq!(&buffer, b"World");
+/// # fs::remove_dir_all(&directory)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PositionalFile {
+    file: Arc<File>,
+    len: u64,
+    position: u64,
+    // serializes the seek and the read of the clones
+    #[cfg(not(any(unix, windows)))]
+    lock: Arc<Mutex<()>>,
+}
+
+impl PositionalFile {
+    /// Creates a new [`PositionalFile`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the length of the [`File`] could not be
+    /// determined.
+    pub fn new(file: File) -> io::Result<Self> {
+        Ok(Self {
+            len: file.metadata()?.len(),
+            file: Arc::new(file),
+            position: 0,
+            #[cfg(not(any(unix, windows)))]
+            lock: Arc::new(Mutex::new(())),
+        })
+    }
+
+    /// Returns the length of the [`File`], when the [`PositionalFile`] was
+    /// created.
+    pub const fn len(&self) -> u64 { self.len }
+
+    /// Returns `true`, if the [`File`] was empty.
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns a reference to the underlying [`File`].
+    pub fn get_ref(&self) -> &File { &self.file }
+}
+
+impl Seek for PositionalFile {
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        self.position = seek_position(self.position, self.len, style)?;
+        Ok(self.position)
+    }
+}
+
+impl Read for PositionalFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = self.read_at(buf, self.position)?;
+        self.position += result as u64;
+
+        Ok(result)
+    }
+}
+
+impl PositionalFile {
+    #[cfg(unix)]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        use std::os::unix::fs::FileExt;
+
+        self.file.read_at(buf, offset)
+    }
+
+    #[cfg(windows)]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        use std::os::windows::fs::FileExt;
+
+        self.file.seek_read(buf, offset)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        let _lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut file = &*self.file;
+        file.seek(SeekFrom::Start(offset))?;
+        file.read(buf)
+    }
+}
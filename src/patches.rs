@@ -0,0 +1,135 @@
+use std::cmp;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::ops::Range;
+
+/// A sparse map of byte patches, overlapping or adjacent patches are merged.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Patches {
+    // maps the offset to the patched bytes, patches never overlap or touch
+    // each other.
+    patches: BTreeMap<u64, Vec<u8>>,
+}
+
+impl Patches {
+    pub fn new() -> Self { Self::default() }
+
+    /// Stores `buf` at `offset`, merging it with all overlapping or adjacent
+    /// patches.
+    pub fn insert(&mut self, offset: u64, buf: &[u8]) {
+        let end = offset + buf.len() as u64;
+
+        let mut start = offset;
+        let mut merged_end = end;
+        let touching = self
+            .patches
+            .range(..=end)
+            .rev()
+            .take_while(|(key, bytes)| **key + bytes.len() as u64 >= offset)
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+
+        let mut parts = vec![];
+        for key in touching {
+            let bytes = self.patches.remove(&key).unwrap_or_default();
+            start = cmp::min(start, key);
+            merged_end = cmp::max(merged_end, key + bytes.len() as u64);
+            parts.push((key, bytes));
+        }
+
+        let mut merged = vec![0; index(merged_end - start)];
+        for (key, bytes) in parts {
+            let from = index(key - start);
+            merged[from..from + bytes.len()].copy_from_slice(&bytes);
+        }
+
+        let from = index(offset - start);
+        merged[from..from + buf.len()].copy_from_slice(buf);
+
+        self.patches.insert(start, merged);
+    }
+
+    /// Copies all patched bytes in `offset..offset + buf.len()` into `buf`.
+    pub fn apply(&self, offset: u64, buf: &mut [u8]) {
+        let end = offset + buf.len() as u64;
+
+        for (key, bytes) in self.patches.range(..end) {
+            let patch_end = key + bytes.len() as u64;
+            if patch_end <= offset {
+                continue;
+            }
+
+            let from = cmp::max(offset, *key);
+            let to = cmp::min(end, patch_end);
+
+            buf[index(from - offset)..index(to - offset)]
+                .copy_from_slice(&bytes[index(from - key)..index(to - key)]);
+        }
+    }
+
+    /// Returns the patched bytes from `offset` until the end of the patch,
+    /// that contains `offset`.
+    pub fn get(&self, offset: u64) -> Option<&[u8]> {
+        let (key, bytes) = self.patches.range(..=offset).next_back()?;
+
+        let from = index(offset - key);
+        if from < bytes.len() {
+            Some(&bytes[from..])
+        } else {
+            None
+        }
+    }
+
+    /// Returns all patched ranges in ascending order.
+    pub fn ranges(&self) -> Vec<Range<u64>> {
+        self.patches
+            .iter()
+            .map(|(offset, bytes)| *offset..*offset + bytes.len() as u64)
+            .collect()
+    }
+
+    /// Returns an iterator over all patches in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &[u8])> {
+        self.patches
+            .iter()
+            .map(|(offset, bytes)| (*offset, bytes.as_slice()))
+    }
+
+    pub fn is_empty(&self) -> bool { self.patches.is_empty() }
+
+    pub fn clear(&mut self) { self.patches.clear() }
+}
+
+/// Converts the distance between two offsets of a patch into an index, the
+/// distance is never larger than the length of a patch.
+fn index(distance: u64) -> usize { usize::try_from(distance).unwrap_or(usize::MAX) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_insert() {
+        let mut patches = Patches::new();
+
+        patches.insert(4, &[1, 1]);
+        patches.insert(10, &[2, 2, 2]);
+        assert_eq!(patches.ranges(), vec![4..6, 10..13]);
+
+        // adjacent patches are merged
+        patches.insert(6, &[3]);
+        assert_eq!(patches.ranges(), vec![4..7, 10..13]);
+
+        // a patch spanning multiple patches
+        patches.insert(5, &[4, 4, 4, 4, 4, 4]);
+        assert_eq!(patches.ranges(), vec![4..13]);
+        assert_eq!(patches.get(4), Some(&[1, 4, 4, 4, 4, 4, 4, 2, 2][..]));
+        assert_eq!(patches.get(11), Some(&[2, 2][..]));
+        assert_eq!(patches.get(13), None);
+
+        let mut buffer = [0; 8];
+        patches.apply(2, &mut buffer);
+        assert_eq!(buffer, [0, 0, 1, 4, 4, 4, 4, 4]);
+    }
+}
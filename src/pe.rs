@@ -0,0 +1,351 @@
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek};
+
+use crate::endian::Endian::Little;
+use crate::SubCursor;
+
+/// The index of the certificate table, which is the only data directory,
+/// that contains a file offset instead of a virtual address.
+const CERTIFICATE_TABLE: usize = 4;
+const SECTION_HEADER_LEN: usize = 40;
+
+/// A section of a PE file (like `.text` or `.rsrc`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeSection {
+    name: String,
+    virtual_size: u32,
+    virtual_address: u32,
+    raw_size: u32,
+    raw_offset: u32,
+    characteristics: u32,
+}
+
+impl PeSection {
+    /// Returns the name of the section. Long names are resolved through the
+    /// COFF string table.
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Returns the size of the section in memory.
+    pub const fn virtual_size(&self) -> u32 { self.virtual_size }
+
+    /// Returns the address of the section in memory relative to the image
+    /// base.
+    pub const fn virtual_address(&self) -> u32 { self.virtual_address }
+
+    /// Returns the size of the section in the file, which is rounded up to
+    /// the file alignment.
+    pub const fn raw_size(&self) -> u32 { self.raw_size }
+
+    /// Returns the offset of the section in the file.
+    pub const fn raw_offset(&self) -> u32 { self.raw_offset }
+
+    /// Returns the flags of the section.
+    pub const fn characteristics(&self) -> u32 { self.characteristics }
+}
+
+/// An entry of the data directory table of a PE file, like the resources
+/// (index 2), the certificates (index 4) or the debug directory (index 6).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataDirectory {
+    index: usize,
+    virtual_address: u32,
+    size: u32,
+}
+
+impl DataDirectory {
+    /// Returns the index of the entry in the data directory table.
+    pub const fn index(&self) -> usize { self.index }
+
+    /// Returns the address of the data relative to the image base (or the
+    /// file offset of the certificate table).
+    pub const fn virtual_address(&self) -> u32 { self.virtual_address }
+
+    /// Returns the size of the data.
+    pub const fn size(&self) -> u32 { self.size }
+
+    /// Returns `true`, if the file does not contain the data.
+    pub const fn is_empty(&self) -> bool { self.virtual_address == 0 || self.size == 0 }
+}
+
+/// A [`PeFile`] reads the headers and the section table of a PE file (like
+/// `.exe` or `.dll` files), that is located in a [`SubCursor`].
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{PeFile, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// let mut data = vec![0; 64 + 24];
+/// data[..2].copy_from_slice(b"MZ");
+/// data[0x3c] = 64;
+/// data[64..68].copy_from_slice(b"PE\0\0");
+/// data[68..70].copy_from_slice(&0x8664_u16.to_le_bytes());
+///
+/// let pe = PeFile::new(SubCursor::from(data))?;
+/// assert_eq!(pe.machine(), 0x8664);
+/// assert!(pe.sections().is_empty());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Note
+///
+/// PE32 and PE32+ files are supported. Long section names (like
+/// `.debug_info` in files of MinGW) are resolved through the COFF string
+/// table.
+#[derive(Debug, Clone)]
+pub struct PeFile<T> {
+    sub_cursor: SubCursor<T>,
+    machine: u16,
+    characteristics: u16,
+    is_64: bool,
+    entry_point: u32,
+    image_base: u64,
+    sections: Vec<PeSection>,
+    data_directories: Vec<DataDirectory>,
+}
+
+impl<T> PeFile<T>
+where
+    T: Read + Seek,
+{
+    /// Reads the DOS header, the PE header, the optional header and the
+    /// section table of the PE file in the [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if a signature or a header is invalid or if
+    /// the [`SubCursor`] could not be read.
+    pub fn new(sub_cursor: SubCursor<T>) -> io::Result<Self> {
+        let mut dos_header = [0; 64];
+        sub_cursor.read_exact_at(0, &mut dos_header)?;
+
+        if &dos_header[..2] != b"MZ" {
+            return Err(invalid_data("invalid dos signature"));
+        }
+
+        let pe_offset = u64::from(Little.u32(&dos_header[0x3c..]));
+
+        let mut pe_header = [0; 24];
+        sub_cursor.read_exact_at(pe_offset, &mut pe_header)?;
+
+        if &pe_header[..4] != b"PE\0\0" {
+            return Err(invalid_data("invalid pe signature"));
+        }
+
+        let machine = Little.u16(&pe_header[4..]);
+        let section_count = usize::from(Little.u16(&pe_header[6..]));
+        let symbol_table = u64::from(Little.u32(&pe_header[12..]));
+        let symbol_count = u64::from(Little.u32(&pe_header[16..]));
+        let optional_header_size = Little.u16(&pe_header[20..]);
+        let characteristics = Little.u16(&pe_header[22..]);
+
+        let mut optional_header = vec![0; usize::from(optional_header_size)];
+        sub_cursor.read_exact_at(pe_offset + 24, &mut optional_header)?;
+        let optional_header = OptionalHeader::parse(&optional_header)?;
+
+        let table_offset = pe_offset + 24 + u64::from(optional_header_size);
+        let mut table = vec![0; section_count * SECTION_HEADER_LEN];
+        sub_cursor.read_exact_at(table_offset, &mut table)?;
+
+        // long names of object files are stored in the string table after
+        // the symbol table (18 bytes per symbol)
+        let strings = {
+            if symbol_table != 0
+                && table
+                    .chunks_exact(SECTION_HEADER_LEN)
+                    .any(|entry| entry[0] == b'/')
+            {
+                let mut len = [0; 4];
+                let strings_offset = symbol_table + symbol_count * 18;
+                sub_cursor.read_exact_at(strings_offset, &mut len)?;
+                let mut strings = vec![];
+                sub_cursor
+                    .child(strings_offset, u64::from(Little.u32(&len)))
+                    .ok_or_else(|| invalid_data("the string table is out of bounds"))?
+                    .read_to_end(&mut strings)?;
+                strings
+            } else {
+                vec![]
+            }
+        };
+
+        let sections = table
+            .chunks_exact(SECTION_HEADER_LEN)
+            .map(|entry| parse_section(entry, &strings))
+            .collect();
+
+        Ok(Self {
+            sub_cursor,
+            machine,
+            characteristics,
+            is_64: optional_header.is_64,
+            entry_point: optional_header.entry_point,
+            image_base: optional_header.image_base,
+            sections,
+            data_directories: optional_header.data_directories,
+        })
+    }
+
+    /// Returns the raw data of `section` in the file as a [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the section is out of bounds.
+    pub fn section_data(&self, section: &PeSection) -> io::Result<SubCursor<T>> {
+        self.sub_cursor
+            .child(u64::from(section.raw_offset), u64::from(section.raw_size))
+            .ok_or_else(|| invalid_data("the section is out of bounds"))
+    }
+
+    /// Returns the data of a data directory (like the resources) as a
+    /// [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the data is not inside of a section or
+    /// if it is out of bounds.
+    pub fn directory_data(&self, directory: &DataDirectory) -> io::Result<SubCursor<T>> {
+        let offset = {
+            if directory.index == CERTIFICATE_TABLE {
+                Some(u64::from(directory.virtual_address))
+            } else {
+                self.rva_to_offset(directory.virtual_address)
+            }
+        };
+
+        offset
+            .and_then(|offset| self.sub_cursor.child(offset, u64::from(directory.size)))
+            .ok_or_else(|| invalid_data("the data directory is out of bounds"))
+    }
+}
+
+impl<T> PeFile<T> {
+    /// Returns the architecture of the file.
+    pub const fn machine(&self) -> u16 { self.machine }
+
+    /// Returns the flags of the file.
+    pub const fn characteristics(&self) -> u16 { self.characteristics }
+
+    /// Returns `true` for a PE32+ (64-bit) file.
+    pub const fn is_64(&self) -> bool { self.is_64 }
+
+    /// Returns the address of the entry point relative to the image base.
+    pub const fn entry_point(&self) -> u32 { self.entry_point }
+
+    /// Returns the preferred address of the image in memory.
+    pub const fn image_base(&self) -> u64 { self.image_base }
+
+    /// Returns all sections in the order of the section table.
+    pub fn sections(&self) -> &[PeSection] { &self.sections }
+
+    /// Returns the first section with the provided name.
+    pub fn section_by_name(&self, name: &str) -> Option<&PeSection> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Returns the entries of the data directory table.
+    pub fn data_directories(&self) -> &[DataDirectory] { &self.data_directories }
+
+    /// Converts an address relative to the image base to an offset in the
+    /// file or returns `None`, if no section contains the address.
+    pub fn rva_to_offset(&self, rva: u32) -> Option<u64> {
+        self.sections
+            .iter()
+            .find(|section| {
+                let size = section.virtual_size.max(section.raw_size);
+                rva >= section.virtual_address
+                    && u64::from(rva) < u64::from(section.virtual_address) + u64::from(size)
+            })
+            .filter(|section| rva - section.virtual_address < section.raw_size)
+            .map(|section| u64::from(section.raw_offset) + u64::from(rva - section.virtual_address))
+    }
+
+    /// Consumes this [`PeFile`], returning the [`SubCursor`].
+    pub fn into_inner(self) -> SubCursor<T> { self.sub_cursor }
+}
+
+/// The fields of the optional header, that are used by [`PeFile`].
+struct OptionalHeader {
+    is_64: bool,
+    entry_point: u32,
+    image_base: u64,
+    data_directories: Vec<DataDirectory>,
+}
+
+impl OptionalHeader {
+    /// Parses the optional header, which is missing in object files.
+    fn parse(header: &[u8]) -> io::Result<Self> {
+        let mut result = Self {
+            is_64: false,
+            entry_point: 0,
+            image_base: 0,
+            data_directories: vec![],
+        };
+
+        if header.len() < 2 {
+            return Ok(result);
+        }
+
+        // the image base and the data directories are shifted in PE32+
+        let directories = match Little.u16(header) {
+            0x10b if header.len() >= 96 => {
+                result.image_base = u64::from(Little.u32(&header[28..]));
+                92
+            }
+            0x20b if header.len() >= 112 => {
+                result.is_64 = true;
+                result.image_base = Little.u64(&header[24..]);
+                108
+            }
+            _ => return Err(invalid_data("invalid optional header")),
+        };
+
+        result.entry_point = Little.u32(&header[16..]);
+
+        let count = usize::try_from(Little.u32(&header[directories..])).unwrap_or(usize::MAX);
+        result.data_directories = header[directories + 4..]
+            .chunks_exact(8)
+            .take(count)
+            .enumerate()
+            .map(|(index, entry)| {
+                DataDirectory {
+                    index,
+                    virtual_address: Little.u32(entry),
+                    size: Little.u32(&entry[4..]),
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+}
+
+/// Parses a section header, long names are looked up in the string table.
+fn parse_section(entry: &[u8], strings: &[u8]) -> PeSection {
+    let mut name = &entry[..8];
+    if name[0] == b'/' {
+        name = String::from_utf8_lossy(&name[1..])
+            .trim_end_matches('\0')
+            .parse::<usize>()
+            .ok()
+            .and_then(|offset| strings.get(offset..))
+            .unwrap_or(name);
+    }
+    let end = name
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(name.len());
+
+    PeSection {
+        name: String::from_utf8_lossy(&name[..end]).into_owned(),
+        virtual_size: Little.u32(&entry[8..]),
+        virtual_address: Little.u32(&entry[12..]),
+        raw_size: Little.u32(&entry[16..]),
+        raw_offset: Little.u32(&entry[20..]),
+        characteristics: Little.u32(&entry[36..]),
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, message) }
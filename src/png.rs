@@ -0,0 +1,212 @@
+use std::cmp;
+use std::io::{self, Read, Seek};
+
+use crate::crc32::Crc32;
+use crate::endian::Endian::Big;
+use crate::search::CHUNK_SIZE;
+use crate::SubCursor;
+
+const SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+impl<T> SubCursor<T>
+where
+    T: Read + Seek,
+{
+    /// Checks the signature of a PNG image and returns an iterator over its
+    /// chunks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+    /// data.extend_from_slice(b"\0\0\0\x04tEXtabcd\x7b\x42\xc4\x45");
+    /// data.extend_from_slice(b"\0\0\0\0IEND\xae\x42\x60\x82");
+    ///
+    /// let chunks = SubCursor::from(data)
+    ///     .png_chunks()?
+    ///     .verify_crc(true)
+    ///     .map(|chunk| chunk.map(|chunk| (chunk.kind(), chunk.data().len())))
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(chunks, vec![(*b"tEXt", 4), (*b"IEND", 0)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the [`SubCursor`] does not start with
+    /// the PNG signature or if it could not be read.
+    pub fn png_chunks(&self) -> io::Result<PngChunks<T>> {
+        let mut signature = [0; 8];
+        self.read_exact_at(0, &mut signature)?;
+
+        if &signature != SIGNATURE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid png signature",
+            ));
+        }
+
+        Ok(PngChunks {
+            sub_cursor: self.sub_cursor(),
+            offset: Some(SIGNATURE.len() as u64),
+            verify_crc: false,
+        })
+    }
+}
+
+/// A chunk of a PNG image, created by [`PngChunks`].
+#[derive(Debug, Clone)]
+pub struct PngChunk<T> {
+    kind: [u8; 4],
+    crc: u32,
+    chunk: SubCursor<T>,
+    data: SubCursor<T>,
+}
+
+impl<T> PngChunk<T> {
+    /// Returns the type of the chunk (like `IHDR`).
+    pub const fn kind(&self) -> [u8; 4] { self.kind }
+
+    /// Returns `true`, if the chunk is critical (like `IHDR`, `PLTE`, `IDAT`
+    /// or `IEND`) and `false`, if it is ancillary (like `tEXt` or `iCCP`).
+    pub const fn is_critical(&self) -> bool { self.kind[0].is_ascii_uppercase() }
+
+    /// Returns the CRC32, that is stored after the data.
+    pub const fn crc(&self) -> u32 { self.crc }
+
+    /// Returns the whole chunk (the length, the type, the data and the CRC),
+    /// which can be copied to another image.
+    pub const fn chunk(&self) -> &SubCursor<T> { &self.chunk }
+
+    /// Returns the data of the chunk.
+    pub const fn data(&self) -> &SubCursor<T> { &self.data }
+}
+
+impl<T> PngChunk<T>
+where
+    T: Read + Seek,
+{
+    /// Computes the CRC32 of the type and the data of the chunk. The data is
+    /// read in blocks, so it does not have to fit in memory.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the data could not be read.
+    pub fn compute_crc(&self) -> io::Result<u32> {
+        let mut crc32 = Crc32::new();
+        crc32.update(&self.kind);
+
+        let len = self.data.len();
+        let mut offset = 0;
+        while offset < len {
+            let block = self
+                .data
+                .read_range(offset, cmp::min(CHUNK_SIZE, len - offset))?;
+
+            if block.is_empty() {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+
+            crc32.update(&block);
+            offset += block.len();
+        }
+
+        Ok(crc32.finish())
+    }
+}
+
+/// An iterator over the chunks of a PNG image, created by
+/// [`SubCursor::png_chunks`]. The iterator ends after the `IEND` chunk.
+///
+/// [`SubCursor::png_chunks`]: crate::SubCursor::png_chunks
+#[derive(Debug, Clone)]
+pub struct PngChunks<T> {
+    sub_cursor: SubCursor<T>,
+    // the offset of the next chunk or `None`, if the iterator is exhausted
+    offset: Option<u64>,
+    verify_crc: bool,
+}
+
+impl<T> PngChunks<T> {
+    /// Sets whether the CRC32 of every chunk should be computed and compared
+    /// with the stored one. A mismatch is returned as an error with
+    /// [`io::ErrorKind::InvalidData`].
+    ///
+    /// # Note
+    ///
+    /// The default is `false`, because the data of every chunk has to be
+    /// read.
+    #[must_use]
+    pub fn verify_crc(self, value: bool) -> Self {
+        Self {
+            verify_crc: value,
+            ..self
+        }
+    }
+}
+
+impl<T> PngChunks<T>
+where
+    T: Read + Seek,
+{
+    fn next_chunk(&mut self, offset: u64) -> io::Result<Option<PngChunk<T>>> {
+        if offset == self.sub_cursor.len() as u64 {
+            return Ok(None);
+        }
+
+        let mut header = [0; 8];
+        self.sub_cursor.read_exact_at(offset, &mut header)?;
+
+        let len = u64::from(Big.u32(&header));
+        let kind = [header[4], header[5], header[6], header[7]];
+
+        let invalid_data = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let out_of_bounds = || invalid_data(format!("the chunk at {offset} is out of bounds"));
+
+        let chunk = self
+            .sub_cursor
+            .child(offset, len + 12)
+            .ok_or_else(out_of_bounds)?;
+        let data = chunk.child(8, len).ok_or_else(out_of_bounds)?;
+
+        let mut crc = [0; 4];
+        chunk.read_exact_at(8 + len, &mut crc)?;
+
+        let chunk = PngChunk {
+            kind,
+            crc: Big.u32(&crc),
+            chunk,
+            data,
+        };
+
+        if self.verify_crc && chunk.compute_crc()? != chunk.crc {
+            return Err(invalid_data(format!(
+                "invalid crc of the chunk at {offset}"
+            )));
+        }
+
+        if &kind != b"IEND" {
+            self.offset = Some(offset + len + 12);
+        }
+
+        Ok(Some(chunk))
+    }
+}
+
+impl<T> Iterator for PngChunks<T>
+where
+    T: Read + Seek,
+{
+    type Item = io::Result<PngChunk<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset.take()?;
+
+        self.next_chunk(offset).transpose()
+    }
+}
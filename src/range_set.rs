@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// A set of non-overlapping ranges, adjacent ranges are merged.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet {
+    // maps the start of a range to its end
+    ranges: BTreeMap<u64, u64>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self { Self::default() }
+
+    /// Adds `range` to the set.
+    pub fn insert(&mut self, range: Range<u64>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        let mut start = range.start;
+        let mut end = range.end;
+
+        let touching = self
+            .ranges
+            .range(..=range.end)
+            .rev()
+            .take_while(|(_, end)| **end >= range.start)
+            .map(|(start, end)| (*start, *end))
+            .collect::<Vec<_>>();
+
+        for (key, value) in touching {
+            self.ranges.remove(&key);
+            start = start.min(key);
+            end = end.max(value);
+        }
+
+        self.ranges.insert(start, end);
+    }
+
+    /// Returns all ranges in ascending order.
+    pub fn to_vec(&self) -> Vec<Range<u64>> {
+        self.ranges
+            .iter()
+            .map(|(start, end)| *start..*end)
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool { self.ranges.is_empty() }
+
+    pub fn clear(&mut self) { self.ranges.clear() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_insert() {
+        let mut set = RangeSet::new();
+
+        set.insert(10..20);
+        set.insert(30..40);
+        set.insert(5..5);
+        assert_eq!(set.to_vec(), vec![10..20, 30..40]);
+
+        set.insert(20..25);
+        assert_eq!(set.to_vec(), vec![10..25, 30..40]);
+
+        set.insert(0..2);
+        set.insert(12..35);
+        assert_eq!(set.to_vec(), vec![0..2, 10..40]);
+
+        set.clear();
+        assert!(set.is_empty());
+    }
+}
@@ -0,0 +1,576 @@
+use std::cmp;
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::patches::Patches;
+use crate::utils::{read_varint, seek_position, write_varint};
+
+const MAGIC: &[u8; 6] = b"SCREC\x01";
+
+/// A [`Recorder`] wraps a stream and logs every read, write and seek, that
+/// reaches it, with the absolute offsets of the stream.
+///
+/// All [`SubCursor`]s sharing the [`Recorder`] are recorded. [`SubCursor`]s,
+/// that are [`recorded`], additionally report their window, so every event
+/// tells, which of them issued it. The log can be [`save`]d to a compact file
+/// and later be loaded as a [`Replay`], which serves all recorded reads
+/// without the original data.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{Event, Recorder, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::{Cursor, Read, Seek, SeekFrom};
+/// use std::sync::{Arc, Mutex};
+///
+/// let recorder = Arc::new(Mutex::new(Recorder::new(Cursor::new(
+///     b"Hello World!".to_vec(),
+/// ))));
+///
+/// let mut sub_cursor = SubCursor::from(recorder.clone())
+///     .start(6)
+///     .end(12)
+///     .preserve(false)
+///     .recorded();
+///
+/// sub_cursor.seek(SeekFrom::Start(1))?;
+/// sub_cursor.read_exact(&mut [0; 4])?;
+///
+/// assert_eq!(
+///     recorder.lock().unwrap().events(),
+///     &[
+///         Event::Seek {
+///             position: 7,
+///             window: Some(6..12),
+///         },
+///         Event::Read {
+///             offset: 7,
+///             data: b"orld".to_vec(),
+///             window: Some(6..12),
+///         },
+///     ]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Note
+///
+/// The [`SubCursor`] seeks the underlying stream lazily, so a seek of the
+/// [`SubCursor`] is only recorded, when it is followed by a read or a write.
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`recorded`]: crate::SubCursor::recorded
+/// [`save`]: #method.save
+#[derive(Default, Debug, Clone)]
+pub struct Recorder<T> {
+    inner: T,
+    position: u64,
+    events: Vec<Event>,
+    origin: Origin,
+}
+
+/// The window of the [`SubCursor`], that is currently accessing a
+/// [`Recorder`].
+///
+/// [`SubCursor`]: crate::SubCursor
+#[derive(Default, Debug, Clone)]
+pub struct Origin(Arc<Mutex<Option<Range<u64>>>>);
+
+impl Origin {
+    /// Attributes all accesses to `window`, until the returned guard is
+    /// dropped.
+    pub(crate) fn enter(&self, window: Range<u64>) -> OriginGuard {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(window);
+        OriginGuard(self.clone())
+    }
+
+    fn get(&self) -> Option<Range<u64>> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+/// Resets the [`Origin`], when it is dropped.
+#[derive(Debug)]
+pub struct OriginGuard(Origin);
+
+impl Drop for OriginGuard {
+    fn drop(&mut self) { *(self.0).0.lock().unwrap_or_else(PoisonError::into_inner) = None; }
+}
+
+/// An operation, that has been recorded by a [`Recorder`].
+///
+/// All offsets are absolute offsets of the underlying stream. The `window` is
+/// the absolute range of the [`SubCursor`], that issued the operation, or
+/// `None`, if it has not been [`recorded`].
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`recorded`]: crate::SubCursor::recorded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// `data` has been read at `offset`.
+    Read {
+        /// The offset of the first byte.
+        offset: u64,
+        /// The bytes, that have been read.
+        data: Vec<u8>,
+        /// The window, that issued the read.
+        window: Option<Range<u64>>,
+    },
+    /// `len` bytes have been written at `offset`.
+    Write {
+        /// The offset of the first byte.
+        offset: u64,
+        /// The number of bytes, that have been written.
+        len: u64,
+        /// The 64-bit FNV-1a hash of the written bytes.
+        hash: u64,
+        /// The window, that issued the write.
+        window: Option<Range<u64>>,
+    },
+    /// The stream has been seeked to `position`.
+    Seek {
+        /// The new position.
+        position: u64,
+        /// The window, that issued the seek.
+        window: Option<Range<u64>>,
+    },
+}
+
+impl Event {
+    /// Returns the 64-bit FNV-1a hash of `data`, like it is used for
+    /// [`Event::Write`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::Event;
+    /// assert_eq!(Event::hash(b""), 0xcbf2_9ce4_8422_2325);
+    /// assert_eq!(Event::hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    /// ```
+    pub fn hash(data: &[u8]) -> u64 {
+        data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    /// Returns the window of the [`SubCursor`], that issued the event.
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    pub const fn window(&self) -> Option<&Range<u64>> {
+        match self {
+            Self::Read { window, .. } | Self::Write { window, .. } | Self::Seek { window, .. } => {
+                window.as_ref()
+            }
+        }
+    }
+
+    /// Returns the offset after the last byte, that has been accessed by the
+    /// event or the position of a seek.
+    const fn end(&self) -> u64 {
+        match self {
+            Self::Read { offset, data, .. } => offset.saturating_add(data.len() as u64),
+            Self::Write { offset, len, .. } => offset.saturating_add(*len),
+            Self::Seek { position, .. } => *position,
+        }
+    }
+}
+
+impl<T> Recorder<T>
+where
+    T: Seek,
+{
+    /// Creates a new [`Recorder`], that wraps `inner`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::Recorder;
+    /// use std::io::Cursor;
+    ///
+    /// let recorder = Recorder::new(Cursor::new(vec![1, 2, 3]));
+    ///
+    /// assert!(recorder.events().is_empty());
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// If the current position of `inner` can not be determined, `0` will be
+    /// assumed.
+    pub fn new(mut inner: T) -> Self {
+        Self {
+            position: inner.stream_position().unwrap_or(0),
+            inner,
+            events: vec![],
+            origin: Origin::default(),
+        }
+    }
+}
+
+impl<T> Recorder<T> {
+    /// Returns all events, that have been recorded so far.
+    pub fn events(&self) -> &[Event] { &self.events }
+
+    /// Forgets all recorded events.
+    pub fn clear_events(&mut self) { self.events.clear() }
+
+    /// Writes all recorded events to `writer`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{Recorder, Replay};
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::{Cursor, Read};
+    ///
+    /// let mut recorder = Recorder::new(Cursor::new(vec![1, 2, 3, 4]));
+    /// recorder.read_exact(&mut [0; 2])?;
+    ///
+    /// let mut file = vec![];
+    /// recorder.save(&mut file)?;
+    ///
+    /// let replay = Replay::load(file.as_slice())?;
+    /// assert_eq!(replay.events(), recorder.events());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if `writer` could not be written.
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+
+        for event in &self.events {
+            match event {
+                Event::Read { offset, data, .. } => {
+                    writer.write_all(&[0])?;
+                    write_varint(&mut writer, *offset)?;
+                    write_varint(&mut writer, data.len() as u64)?;
+                    writer.write_all(data)?;
+                }
+                Event::Write {
+                    offset, len, hash, ..
+                } => {
+                    writer.write_all(&[1])?;
+                    write_varint(&mut writer, *offset)?;
+                    write_varint(&mut writer, *len)?;
+                    writer.write_all(&hash.to_le_bytes())?;
+                }
+                Event::Seek { position, .. } => {
+                    writer.write_all(&[2])?;
+                    write_varint(&mut writer, *position)?;
+                }
+            }
+
+            // the window is stored as a flag, followed by its start and its
+            // length
+            match event.window() {
+                Some(window) => {
+                    writer.write_all(&[1])?;
+                    write_varint(&mut writer, window.start)?;
+                    write_varint(&mut writer, window.end - window.start)?;
+                }
+                None => writer.write_all(&[0])?,
+            }
+        }
+
+        writer.flush()
+    }
+
+    /// Creates a [`Replay`], that serves all recorded reads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{Recorder, SubCursor};
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::{Cursor, Read};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let recorder = Arc::new(Mutex::new(Recorder::new(Cursor::new(
+    ///     b"Hello World!".to_vec(),
+    /// ))));
+    /// let mut sub_cursor = SubCursor::from(recorder.clone()).start(6).end(12);
+    ///
+    /// let mut original = String::new();
+    /// sub_cursor.read_to_string(&mut original)?;
+    ///
+    /// // the original data is no longer needed:
+    /// let replay = Arc::new(Mutex::new(recorder.lock().unwrap().replay()));
+    /// let mut sub_cursor = SubCursor::from(replay).start(6).end(12);
+    ///
+    /// let mut replayed = String::new();
+    /// sub_cursor.read_to_string(&mut replayed)?;
+    /// assert_eq!(replayed, original);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn replay(&self) -> Replay { Replay::new(self.events.clone()) }
+
+    /// Returns a reference to the underlying stream.
+    pub const fn get_ref(&self) -> &T { &self.inner }
+
+    /// Returns a mutable reference to the underlying stream.
+    ///
+    /// # Note
+    ///
+    /// Accesses through the returned reference will not be recorded and
+    /// seeking it will confuse the [`Recorder`].
+    pub const fn get_mut(&mut self) -> &mut T { &mut self.inner }
+
+    /// Consumes the [`Recorder`], returning the underlying stream.
+    pub fn into_inner(self) -> T { self.inner }
+
+    /// Returns the window of the [`SubCursor`], that is currently accessing
+    /// the [`Recorder`].
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    pub(crate) const fn origin(&self) -> &Origin { &self.origin }
+}
+
+impl<T> Seek for Recorder<T>
+where
+    T: Seek,
+{
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(style)?;
+        self.events.push(Event::Seek {
+            position: self.position,
+            window: self.origin.get(),
+        });
+
+        Ok(self.position)
+    }
+}
+
+impl<T> Read for Recorder<T>
+where
+    T: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = self.inner.read(buf)?;
+
+        self.events.push(Event::Read {
+            offset: self.position,
+            data: buf[..result].to_vec(),
+            window: self.origin.get(),
+        });
+        self.position += result as u64;
+
+        Ok(result)
+    }
+}
+
+impl<T> Write for Recorder<T>
+where
+    T: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let result = self.inner.write(buf)?;
+
+        self.events.push(Event::Write {
+            offset: self.position,
+            len: result as u64,
+            hash: Event::hash(&buf[..result]),
+            window: self.origin.get(),
+        });
+        self.position += result as u64;
+
+        Ok(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+/// A [`Replay`] is a stream, that serves the reads of a [`Recorder`].
+///
+/// Reading data, that has not been recorded, will fail. Writes are accepted
+/// and will be visible for subsequent reads.
+///
+/// The length of the stream is the largest offset, that has been recorded.
+#[derive(Default, Debug, Clone)]
+pub struct Replay {
+    events: Vec<Event>,
+    data: Patches,
+    len: u64,
+    position: u64,
+}
+
+impl Replay {
+    /// Creates a new [`Replay`], that serves the reads of `events`.
+    pub fn new(events: Vec<Event>) -> Self {
+        let mut data = Patches::new();
+        let mut len = 0;
+
+        for event in &events {
+            len = cmp::max(len, event.end());
+
+            if let Event::Read {
+                offset,
+                data: bytes,
+                ..
+            } = event
+            {
+                data.insert(*offset, bytes);
+            }
+        }
+
+        Self {
+            events,
+            data,
+            len,
+            position: 0,
+        }
+    }
+
+    /// Reads events, that have been written with [`Recorder::save`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the data is not a valid recording.
+    pub fn load<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; 6];
+        reader.read_exact(&mut magic)?;
+
+        if &magic != MAGIC {
+            return Err(invalid_recording("invalid recording header"));
+        }
+
+        let mut events = vec![];
+        loop {
+            let mut tag = [0; 1];
+            if reader.read(&mut tag)? == 0 {
+                break;
+            }
+
+            let window = |reader: &mut R| {
+                let mut flag = [0; 1];
+                reader.read_exact(&mut flag)?;
+                match flag[0] {
+                    0 => Ok(None),
+                    1 => {
+                        let start = read_varint(reader)?;
+                        let len = read_varint(reader)?;
+                        check_access(start, len)?;
+                        Ok(Some(start..start + len))
+                    }
+                    _ => Err(invalid_recording("invalid window in recording")),
+                }
+            };
+
+            let event = {
+                match tag[0] {
+                    0 => {
+                        let offset = read_varint(&mut reader)?;
+                        let len = read_varint(&mut reader)?;
+                        check_access(offset, len)?;
+
+                        let mut data = vec![];
+                        reader.by_ref().take(len).read_to_end(&mut data)?;
+                        if data.len() as u64 != len {
+                            return Err(io::ErrorKind::UnexpectedEof.into());
+                        }
+
+                        Event::Read {
+                            offset,
+                            data,
+                            window: window(&mut reader)?,
+                        }
+                    }
+                    1 => {
+                        let offset = read_varint(&mut reader)?;
+                        let len = read_varint(&mut reader)?;
+                        check_access(offset, len)?;
+
+                        let mut hash = [0; 8];
+                        reader.read_exact(&mut hash)?;
+
+                        Event::Write {
+                            offset,
+                            len,
+                            hash: u64::from_le_bytes(hash),
+                            window: window(&mut reader)?,
+                        }
+                    }
+                    2 => {
+                        Event::Seek {
+                            position: read_varint(&mut reader)?,
+                            window: window(&mut reader)?,
+                        }
+                    }
+                    _ => return Err(invalid_recording("invalid event in recording")),
+                }
+            };
+
+            events.push(event);
+        }
+
+        Ok(Self::new(events))
+    }
+
+    /// Returns the events, that are replayed.
+    pub fn events(&self) -> &[Event] { &self.events }
+}
+
+impl Seek for Replay {
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        self.position = seek_position(self.position, self.len, style)?;
+        Ok(self.position)
+    }
+}
+
+impl Read for Replay {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.len {
+            return Ok(0);
+        }
+
+        let available = self.data.get(self.position).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("offset {} has not been recorded", self.position),
+            )
+        })?;
+
+        let remaining = usize::try_from(self.len - self.position).unwrap_or(usize::MAX);
+        let result = cmp::min(cmp::min(buf.len(), available.len()), remaining);
+        buf[..result].copy_from_slice(&available[..result]);
+
+        self.position += result as u64;
+        Ok(result)
+    }
+}
+
+impl Write for Replay {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.insert(self.position, buf);
+        self.position += buf.len() as u64;
+        self.len = cmp::max(self.len, self.position);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+/// Checks, that the end of a read or a write of `len` bytes at `offset` does
+/// not overflow.
+fn check_access(offset: u64, len: u64) -> io::Result<()> {
+    offset
+        .checked_add(len)
+        .map(|_| ())
+        .ok_or_else(|| invalid_recording("the end of an event overflows"))
+}
+
+fn invalid_recording(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,346 @@
+use std::io::{self, Read, Seek, Write};
+
+use crate::SubCursor;
+
+/// A type, that can be decoded from a single record of a [`RecordCursor`].
+///
+/// [`RecordCursor`]: crate::RecordCursor
+pub trait Decode: Sized {
+    /// Decodes a value from `reader`, which contains exactly one record.
+    ///
+    /// # Errors
+    ///
+    /// This function should error, if the record is malformed.
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+/// A type, that can be encoded into a single record of a [`RecordCursor`].
+///
+/// [`RecordCursor`]: crate::RecordCursor
+pub trait Encode {
+    /// Encodes the value into `writer`.
+    ///
+    /// # Errors
+    ///
+    /// This function should error, if the value can not be encoded.
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+impl Decode for Vec<u8> {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut result = vec![];
+        reader.read_to_end(&mut result)?;
+        Ok(result)
+    }
+}
+
+impl Encode for [u8] {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> { writer.write_all(self) }
+}
+
+impl Encode for Vec<u8> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> { writer.write_all(self) }
+}
+
+/// A [`RecordCursor`] is a view of a table with fixed-size records (like
+/// index or directory entries), that is located in a [`SubCursor`].
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{RecordCursor, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::Read;
+///
+/// // a header followed by three 4 byte records
+/// let sub_cursor = SubCursor::from(b"HEADaaaabbbbcccc".to_vec()).start(4);
+/// let records = RecordCursor::new(sub_cursor, 4, 3)?;
+///
+/// assert_eq!(records.len(), 3);
+///
+/// let mut record = records.get(1).unwrap();
+/// let mut buffer = String::new();
+/// record.read_to_string(&mut buffer)?;
+/// assert_eq!(buffer, "bbbb".to_string());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordCursor<T> {
+    sub_cursor: SubCursor<T>,
+    record_size: usize,
+    count: usize,
+}
+
+impl<T> RecordCursor<T> {
+    /// Creates a new [`RecordCursor`] with `count` records, that are
+    /// `record_size` bytes long. The table starts at the start of the
+    /// [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the records do not fit in the
+    /// [`SubCursor`].
+    // takes ownership, because the table replaces the window it is built on
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn new(sub_cursor: SubCursor<T>, record_size: usize, count: usize) -> io::Result<Self> {
+        let len = record_size
+            .checked_mul(count)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the table is too large"))?;
+
+        if len > sub_cursor.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "{} records of {} bytes do not fit in {} bytes",
+                    count,
+                    record_size,
+                    sub_cursor.len()
+                ),
+            ));
+        }
+
+        let start = sub_cursor.get_start();
+        Ok(Self {
+            sub_cursor: sub_cursor.end(start + len),
+            record_size,
+            count,
+        })
+    }
+
+    /// Returns the number of records.
+    pub const fn len(&self) -> usize { self.count }
+
+    /// Returns `true`, if there are no records.
+    pub const fn is_empty(&self) -> bool { self.count == 0 }
+
+    /// Returns the size of a single record in bytes.
+    pub const fn record_size(&self) -> usize { self.record_size }
+
+    /// Returns the [`SubCursor`], that contains all records.
+    pub fn sub_cursor(&self) -> SubCursor<T> { self.sub_cursor.sub_cursor() }
+
+    /// Returns the record at `index` as a [`SubCursor`] or `None` if `index`
+    /// is out of bounds.
+    pub fn get(&self, index: usize) -> Option<SubCursor<T>> {
+        if index >= self.count {
+            return None;
+        }
+
+        self.sub_cursor
+            .child((index * self.record_size) as u64, self.record_size as u64)
+    }
+
+    /// Returns an iterator over all records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// let records = SubCursor::from(vec![0; 10]).records(3);
+    ///
+    /// assert_eq!(
+    ///     records
+    ///         .iter()
+    ///         .map(|record| record.len())
+    ///         .collect::<Vec<_>>(),
+    ///     vec![3, 3, 3]
+    /// );
+    /// ```
+    pub fn iter(&self) -> Records<T> {
+        Records {
+            record_cursor: Self {
+                sub_cursor: self.sub_cursor(),
+                record_size: self.record_size,
+                count: self.count,
+            },
+            front: 0,
+            back: self.count,
+        }
+    }
+}
+
+impl<T> RecordCursor<T>
+where
+    T: Read + Seek,
+{
+    /// Decodes the record at `index`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{Decode, SubCursor};
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::Read;
+    ///
+    /// struct Entry(u16);
+    ///
+    /// impl Decode for Entry {
+    ///     fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+    ///         let mut buffer = [0; 2];
+    ///         reader.read_exact(&mut buffer)?;
+    ///         Ok(Self(u16::from_le_bytes(buffer)))
+    ///     }
+    /// }
+    ///
+    /// let records = SubCursor::from(vec![1, 0, 2, 0, 3, 0]).records(2);
+    /// assert_eq!(records.decode::<Entry>(2)?.0, 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if `index` is out of bounds or if the
+    /// record could not be decoded.
+    pub fn decode<D: Decode>(&self, index: usize) -> io::Result<D> {
+        let mut record = self
+            .get(index)
+            .ok_or_else(|| out_of_bounds(index, self.count))?;
+        D::decode(&mut record)
+    }
+}
+
+impl<T> RecordCursor<T>
+where
+    T: Write + Seek,
+{
+    /// Overwrites the record at `index` with `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::Cursor;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let cursor = Arc::new(Mutex::new(Cursor::new(b"aabbcc".to_vec())));
+    /// let records = SubCursor::from(cursor.clone()).end(6).records(2);
+    ///
+    /// records.set(1, &b"BB"[..])?;
+    /// assert_eq!(cursor.lock().unwrap().get_ref(), b"aaBBcc");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if `index` is out of bounds or if the
+    /// encoded value is not exactly [`record_size`] bytes long.
+    ///
+    /// [`record_size`]: RecordCursor::record_size
+    pub fn set<E: Encode + ?Sized>(&self, index: usize, value: &E) -> io::Result<()> {
+        let mut record = self
+            .get(index)
+            .ok_or_else(|| out_of_bounds(index, self.count))?;
+
+        let mut buffer = Vec::with_capacity(self.record_size);
+        value.encode(&mut buffer)?;
+
+        if buffer.len() != self.record_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "the encoded value is {} bytes long, but a record is {} bytes long",
+                    buffer.len(),
+                    self.record_size
+                ),
+            ));
+        }
+
+        record.write_all(&buffer)
+    }
+}
+
+impl<T> SubCursor<T> {
+    /// Interprets the whole [`SubCursor`] as a table of `record_size` bytes
+    /// long records. Remaining bytes at the end are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// let records = SubCursor::from(vec![0; 10]).records(4);
+    ///
+    /// assert_eq!(records.len(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `record_size` is 0.
+    pub fn records(&self, record_size: usize) -> RecordCursor<T> {
+        assert_ne!(record_size, 0, "the size of a record must not be 0");
+
+        let count = self.len() / record_size;
+        let start = self.get_start();
+
+        RecordCursor {
+            sub_cursor: self.end(start + count * record_size),
+            record_size,
+            count,
+        }
+    }
+}
+
+fn out_of_bounds(index: usize, count: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("record {index} is out of bounds (records: {count})"),
+    )
+}
+
+impl<T> IntoIterator for &RecordCursor<T> {
+    type IntoIter = Records<T>;
+    type Item = SubCursor<T>;
+
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+/// An iterator over the records of a [`RecordCursor`], created by
+/// [`RecordCursor::iter`].
+///
+/// [`RecordCursor`]: crate::RecordCursor
+/// [`RecordCursor::iter`]: crate::RecordCursor::iter
+#[derive(Debug, Clone)]
+pub struct Records<T> {
+    record_cursor: RecordCursor<T>,
+    front: usize,
+    back: usize,
+}
+
+impl<T> Iterator for Records<T> {
+    type Item = SubCursor<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        let result = self.record_cursor.get(self.front);
+        self.front += 1;
+
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let result = self.back - self.front;
+        (result, Some(result))
+    }
+}
+
+impl<T> DoubleEndedIterator for Records<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        self.record_cursor.get(self.back)
+    }
+}
+
+impl<T> ExactSizeIterator for Records<T> {}
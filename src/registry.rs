@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A [`Registry`] keeps track of all resizes, that happened to a buffer, so
+/// every [`SubCursor`] registered with it, can adjust its [`start`] and
+/// [`end`] to keep pointing at the same data.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{Registry, SubCursor};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::Read;
+///
+/// let registry = Registry::new();
+/// let root = SubCursor::from(b"firstsecond".to_vec()).registry(&registry);
+///
+/// let mut first = root.end(5);
+/// let mut second = root.start(5);
+///
+/// // grow the first entry by 3 bytes
+/// first.resize(8);
+///
+/// // the second entry has been moved automatically:
+/// second.sync();
+/// assert_eq!(second.get_start(), 8);
+/// assert_eq!(second.get_end(), 14);
+///
+/// let mut result = String::new();
+/// second.read_to_string(&mut result)?;
+/// assert_eq!(result, "second".to_string());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Note
+///
+/// A [`SubCursor`] only knows about the resizes, that happened after it has
+/// been registered. They are applied, when it is used the next time (or by
+/// [`SubCursor::sync`]). Resizes, that have been applied by all registered
+/// [`SubCursor`]s, are forgotten.
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`SubCursor::sync`]: crate::SubCursor::sync
+/// [`start`]: crate::SubCursor::start
+/// [`end`]: crate::SubCursor::end
+#[derive(Default, Debug, Clone)]
+pub struct Registry {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default, Debug)]
+struct State {
+    edits: Vec<Edit>,
+    // the generation of the first edit in `edits`
+    base: usize,
+    // maps a generation to the number of registered SubCursors, that are at
+    // this generation
+    live: BTreeMap<usize, usize>,
+}
+
+impl State {
+    const fn generation(&self) -> usize { self.base + self.edits.len() }
+
+    fn register(&mut self, generation: usize) { *self.live.entry(generation).or_insert(0) += 1; }
+
+    fn unregister(&mut self, generation: usize) {
+        if let Some(count) = self.live.get_mut(&generation) {
+            *count -= 1;
+            if *count == 0 {
+                self.live.remove(&generation);
+            }
+        }
+
+        // all edits before the oldest generation are no longer needed
+        let oldest = self
+            .live
+            .keys()
+            .next()
+            .copied()
+            .unwrap_or_else(|| self.generation());
+        if oldest > self.base {
+            self.edits.drain(..oldest - self.base);
+            self.base = oldest;
+        }
+    }
+}
+
+/// The membership of a [`SubCursor`] in a [`Registry`], which remembers up to
+/// which generation the edits have been applied.
+///
+/// [`SubCursor`]: crate::SubCursor
+#[derive(Debug)]
+pub struct Registration {
+    registry: Registry,
+    generation: usize,
+}
+
+/// Replaces `removed` bytes at `at` with `inserted` bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edit {
+    pub at: usize,
+    pub removed: usize,
+    pub inserted: usize,
+}
+
+impl Edit {
+    /// Returns where `offset` is located after this edit has been applied.
+    pub const fn map(self, offset: usize) -> usize {
+        if offset >= self.at + self.removed {
+            offset - self.removed + self.inserted
+        } else if offset > self.at {
+            // the offset pointed into the removed data
+            self.at
+        } else {
+            offset
+        }
+    }
+}
+
+impl Registry {
+    /// Creates a new, empty [`Registry`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::Registry;
+    /// let registry = Registry::new();
+    /// ```
+    pub fn new() -> Self { Self::default() }
+
+    /// Registers a new [`SubCursor`] at the current generation.
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    pub(crate) fn register(&self) -> Registration {
+        let mut state = self.lock();
+        let generation = state.generation();
+        state.register(generation);
+        drop(state);
+
+        Registration {
+            registry: self.clone(),
+            generation,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Registration {
+    /// Registers another [`SubCursor`], that is at `generation`.
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    pub(crate) fn at(&self, generation: usize) -> Self {
+        self.registry.lock().register(generation);
+
+        Self {
+            registry: self.registry.clone(),
+            generation,
+        }
+    }
+
+    /// Moves the registration to `generation`, all edits before it will no
+    /// longer be applied.
+    pub(crate) fn advance(&mut self, generation: usize) {
+        if generation == self.generation {
+            return;
+        }
+
+        let mut state = self.registry.lock();
+        state.register(generation);
+        state.unregister(self.generation);
+        drop(state);
+        self.generation = generation;
+    }
+
+    /// Records a new edit, that has been applied by the [`SubCursor`] itself.
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    pub(crate) fn push(&mut self, edit: Edit) {
+        let generation = {
+            let mut state = self.registry.lock();
+            state.edits.push(edit);
+            state.generation()
+        };
+
+        self.advance(generation);
+    }
+
+    /// Applies all edits after the generation of this registration to the
+    /// provided bounds and returns the adjusted bounds together with the new
+    /// generation.
+    pub(crate) fn apply(
+        &self,
+        mut start: usize,
+        mut end: usize,
+        mut position: u64,
+    ) -> (usize, usize, u64, usize) {
+        let state = self.registry.lock();
+
+        // the edits before `base` have been applied by every registration, so
+        // `generation` is never smaller than `base`
+        for edit in state.edits.iter().skip(self.generation - state.base) {
+            start = edit.map(start);
+            end = edit.map(end);
+            position =
+                usize::try_from(position).map_or(position, |position| edit.map(position) as u64);
+        }
+
+        // the position has to stay inside of the window:
+        position = position.max(start as u64).min(end as u64);
+
+        (start, end, position, state.generation())
+    }
+}
+
+impl Clone for Registration {
+    fn clone(&self) -> Self { self.at(self.generation) }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) { self.registry.lock().unregister(self.generation); }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SubCursor;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_edit_map() {
+        let grow = Edit {
+            at: 5,
+            removed: 0,
+            inserted: 3,
+        };
+
+        assert_eq!(grow.map(4), 4);
+        assert_eq!(grow.map(5), 8);
+        assert_eq!(grow.map(10), 13);
+
+        let shrink = Edit {
+            at: 5,
+            removed: 3,
+            inserted: 0,
+        };
+
+        assert_eq!(shrink.map(5), 5);
+        assert_eq!(shrink.map(6), 5);
+        assert_eq!(shrink.map(8), 5);
+        assert_eq!(shrink.map(10), 7);
+    }
+
+    #[test]
+    fn test_edits_are_forgotten() {
+        let registry = Registry::new();
+        let root = SubCursor::from(vec![0; 16]).registry(&registry);
+        let mut first = root.end(8);
+        let mut second = root.start(8);
+        drop(root);
+
+        for len in 0..1000 {
+            first.resize(8 + len % 4);
+            second.sync();
+            assert!(registry.lock().edits.len() <= 1);
+        }
+        assert_eq!(second.get_start(), first.get_end());
+
+        // a SubCursor, that has not been synced, keeps its edits alive
+        let stale = first.sub_cursor();
+        for len in 0..10 {
+            first.resize(len);
+            second.sync();
+        }
+        assert_eq!(registry.lock().edits.len(), 10);
+
+        drop(stale);
+        assert!(registry.lock().edits.len() <= 1);
+        assert_eq!(registry.lock().live.values().sum::<usize>(), 2);
+    }
+}
@@ -0,0 +1,443 @@
+use std::cmp;
+use std::io::{self, Read, Seek};
+
+use crate::search::CHUNK_SIZE;
+use crate::SubCursor;
+
+impl<T> SubCursor<T>
+where
+    T: Read + Seek,
+{
+    /// Returns a reader, that reads the bytes of the [`SubCursor`] backwards
+    /// (from the end to the start).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::Read;
+    ///
+    /// let sub_cursor = SubCursor::from(b"Hello World!".to_vec()).end(5);
+    ///
+    /// let mut result = String::new();
+    /// sub_cursor.reverse_reader().read_to_string(&mut result)?;
+    /// assert_eq!(result, "olleH".to_string());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// The [`SubCursor`] is read in blocks, so the window does not have to
+    /// fit in memory.
+    pub fn reverse_reader(&self) -> ReverseReader<T> {
+        ReverseReader {
+            sub_cursor: self.sub_cursor(),
+            buffer: vec![],
+            offset: 0,
+            end: self.len(),
+        }
+    }
+
+    /// Returns an iterator over the parts of the [`SubCursor`], that are
+    /// separated by `delimiter`, starting with the last part.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let sub_cursor = SubCursor::from(b"a||bc||".to_vec());
+    ///
+    /// let parts = sub_cursor
+    ///     .rsplit(b"||")
+    ///     .map(|part| part.map(|part| (part.get_start(), part.get_end())))
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(parts, vec![(7, 7), (3, 5), (0, 1)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delimiter` is empty.
+    pub fn rsplit(&self, delimiter: &[u8]) -> RSplit<T> {
+        assert!(!delimiter.is_empty(), "the delimiter must not be empty");
+
+        RSplit {
+            sub_cursor: self.sub_cursor(),
+            delimiter: delimiter.to_vec(),
+            end: Some(self.len()),
+            buffer: vec![],
+            buffer_start: self.len(),
+            chunk_size: CHUNK_SIZE,
+        }
+    }
+
+    /// Returns an iterator over the lines of the [`SubCursor`], starting with
+    /// the last line. The lines are the same as the ones of [`lines`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::Read;
+    ///
+    /// let sub_cursor = SubCursor::from(b"first\nsecond\r\nthird\n".to_vec());
+    ///
+    /// // the last two lines of a log
+    /// let mut lines = vec![];
+    /// for line in sub_cursor.rlines().take(2) {
+    ///     let mut buffer = String::new();
+    ///     line?.read_to_string(&mut buffer)?;
+    ///     lines.push(buffer);
+    /// }
+    ///
+    /// assert_eq!(lines, vec!["third".to_string(), "second".to_string()]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`lines`]: #method.lines
+    pub fn rlines(&self) -> RLines<T> {
+        RLines {
+            rsplit: self.rsplit(b"\n"),
+            trimmed: false,
+        }
+    }
+}
+
+/// A reader, that reads a [`SubCursor`] backwards, created by
+/// [`SubCursor::reverse_reader`].
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`SubCursor::reverse_reader`]: crate::SubCursor::reverse_reader
+#[derive(Debug, Clone)]
+pub struct ReverseReader<T> {
+    sub_cursor: SubCursor<T>,
+    // the current block in reverse order
+    buffer: Vec<u8>,
+    // the number of bytes of the buffer, that have been read
+    offset: usize,
+    // all bytes before the end have not been buffered yet
+    end: usize,
+}
+
+impl<T> ReverseReader<T> {
+    /// Returns the number of bytes, that have not been read yet.
+    pub const fn remaining(&self) -> usize { self.end + self.buffer.len() - self.offset }
+}
+
+impl<T> Read for ReverseReader<T>
+where
+    T: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.offset == self.buffer.len() {
+            if self.end == 0 {
+                return Ok(0);
+            }
+
+            let start = self.end.saturating_sub(CHUNK_SIZE);
+            self.buffer = self.sub_cursor.read_range(start, self.end - start)?;
+
+            // the underlying stream is shorter than the window
+            if self.buffer.len() != self.end - start {
+                self.buffer.clear();
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+
+            self.buffer.reverse();
+            self.offset = 0;
+            self.end = start;
+        }
+
+        let result = cmp::min(buf.len(), self.buffer.len() - self.offset);
+        buf[..result].copy_from_slice(&self.buffer[self.offset..self.offset + result]);
+        self.offset += result;
+
+        Ok(result)
+    }
+}
+
+/// An iterator over the parts of a [`SubCursor`], that are separated by a
+/// delimiter, created by [`SubCursor::rsplit`].
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`SubCursor::rsplit`]: crate::SubCursor::rsplit
+#[derive(Debug, Clone)]
+pub struct RSplit<T> {
+    sub_cursor: SubCursor<T>,
+    delimiter: Vec<u8>,
+    // the end of the next part or `None`, if the iterator is exhausted
+    end: Option<usize>,
+    // the data before the end of the next part, that has already been read,
+    // so every byte is only read once
+    buffer: Vec<u8>,
+    // the offset of the first byte of the buffer
+    buffer_start: usize,
+    chunk_size: usize,
+}
+
+impl<T> RSplit<T>
+where
+    T: Read + Seek,
+{
+    /// Returns the byte at `offset`, which is usually still in the buffer.
+    fn byte_at(&self, offset: usize) -> io::Result<Option<u8>> {
+        if let Some(byte) = offset
+            .checked_sub(self.buffer_start)
+            .and_then(|index| self.buffer.get(index))
+        {
+            return Ok(Some(*byte));
+        }
+
+        Ok(self.sub_cursor.read_range(offset, 1)?.first().copied())
+    }
+
+    /// Searches backward for a delimiter, that ends at or before `end`.
+    fn rfind_next(&mut self, end: usize) -> io::Result<Option<usize>> {
+        let delimiter_len = self.delimiter.len();
+
+        // the data after `end` has already been searched
+        if end < self.buffer_start {
+            self.buffer.clear();
+            self.buffer_start = end;
+        } else {
+            self.buffer.truncate(end - self.buffer_start);
+        }
+
+        loop {
+            if let Some(index) = self
+                .buffer
+                .windows(delimiter_len)
+                .rposition(|bytes| bytes == &self.delimiter[..])
+            {
+                return Ok(Some(self.buffer_start + index));
+            }
+
+            if self.buffer_start == 0 {
+                return Ok(None);
+            }
+
+            // keep the first `delimiter.len() - 1` bytes, because a match
+            // might straddle two chunks
+            self.buffer.truncate(delimiter_len - 1);
+
+            let start = self.buffer_start.saturating_sub(self.chunk_size);
+            let mut chunk = self
+                .sub_cursor
+                .read_range(start, self.buffer_start - start)?;
+
+            // the underlying stream is shorter than the window
+            if chunk.len() != self.buffer_start - start {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+
+            chunk.extend_from_slice(&self.buffer);
+            self.buffer = chunk;
+            self.buffer_start = start;
+        }
+    }
+
+    /// Returns the start and the end of the next part.
+    fn next_range(&mut self) -> Option<io::Result<(usize, usize)>> {
+        let end = self.end?;
+
+        let start = match self.rfind_next(end) {
+            Ok(Some(found)) => {
+                self.end = Some(found);
+                found + self.delimiter.len()
+            }
+            Ok(None) => {
+                self.end = None;
+                0
+            }
+            Err(error) => {
+                self.end = None;
+                return Some(Err(error));
+            }
+        };
+
+        Some(Ok((start, end)))
+    }
+}
+
+impl<T> Iterator for RSplit<T>
+where
+    T: Read + Seek,
+{
+    type Item = io::Result<SubCursor<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, end) = match self.next_range()? {
+            Ok(range) => range,
+            Err(error) => return Some(Err(error)),
+        };
+
+        self.sub_cursor
+            .child(start as u64, (end - start) as u64)
+            .map(Ok)
+    }
+}
+
+/// An iterator over the lines of a [`SubCursor`], starting with the last
+/// line, created by [`SubCursor::rlines`].
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`SubCursor::rlines`]: crate::SubCursor::rlines
+#[derive(Debug, Clone)]
+pub struct RLines<T> {
+    rsplit: RSplit<T>,
+    // whether the final newline has already been removed
+    trimmed: bool,
+}
+
+impl<T> Iterator for RLines<T>
+where
+    T: Read + Seek,
+{
+    type Item = io::Result<SubCursor<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.trimmed {
+            self.trimmed = true;
+
+            // there is no empty line after a final newline
+            if let Some(len) = self.rsplit.end {
+                let last = {
+                    if len == 0 {
+                        Ok(None)
+                    } else {
+                        self.rsplit.byte_at(len - 1)
+                    }
+                };
+
+                match last {
+                    Ok(Some(b'\n')) => self.rsplit.end = Some(len - 1),
+                    Ok(Some(_)) => {}
+                    Ok(None) => self.rsplit.end = None,
+                    Err(error) => {
+                        self.rsplit.end = None;
+                        return Some(Err(error));
+                    }
+                }
+            }
+        }
+
+        let (start, mut end) = match self.rsplit.next_range()? {
+            Ok(range) => range,
+            Err(error) => return Some(Err(error)),
+        };
+
+        // remove the carriage return of a `\r\n` line ending, which is still
+        // in the buffer of the search
+        if end > start {
+            match self.rsplit.byte_at(end - 1) {
+                Ok(Some(b'\r')) => end -= 1,
+                Ok(_) => {}
+                Err(error) => return Some(Err(error)),
+            }
+        }
+
+        self.rsplit
+            .sub_cursor
+            .child(start as u64, (end - start) as u64)
+            .map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_rsplit_chunk_boundaries() -> io::Result<()> {
+        let sub_cursor = SubCursor::from(b"#a\nbc\r\n\ndef||g".to_vec()).start(1);
+
+        for chunk_size in 1..16 {
+            for (delimiter, expected) in &[
+                (&b"\n"[..], vec![(8, 14), (7, 7), (3, 6), (1, 2)]),
+                (&b"||"[..], vec![(13, 14), (1, 11)]),
+            ] {
+                let rsplit = RSplit {
+                    chunk_size,
+                    ..sub_cursor.rsplit(delimiter)
+                };
+
+                let parts = rsplit
+                    .map(|part| part.map(|part| (part.get_start(), part.get_end())))
+                    .collect::<io::Result<Vec<_>>>()?;
+                assert_eq!(parts, *expected);
+            }
+
+            let rlines = RLines {
+                rsplit: RSplit {
+                    chunk_size,
+                    ..sub_cursor.rsplit(b"\n")
+                },
+                trimmed: false,
+            };
+
+            let lines = rlines
+                .map(|line| line.map(|line| (line.get_start(), line.get_end())))
+                .collect::<io::Result<Vec<_>>>()?;
+            assert_eq!(lines, vec![(8, 14), (7, 7), (3, 5), (1, 2)]);
+        }
+
+        Ok(())
+    }
+
+    /// A stream, that fails the first read.
+    #[derive(Debug)]
+    struct FailOnce {
+        inner: io::Cursor<Vec<u8>>,
+        failed: bool,
+    }
+
+    impl Read for FailOnce {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if !self.failed {
+                self.failed = true;
+                return Err(io::Error::other("broken stream"));
+            }
+
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for FailOnce {
+        fn seek(&mut self, style: io::SeekFrom) -> io::Result<u64> { self.inner.seek(style) }
+    }
+
+    #[test]
+    fn test_rlines_error() -> io::Result<()> {
+        let stream = FailOnce {
+            inner: io::Cursor::new(b"a\nb\n".to_vec()),
+            failed: false,
+        };
+        let sub_cursor = SubCursor::from(stream);
+
+        let mut lines = sub_cursor.rlines();
+        assert!(matches!(
+            lines.next(),
+            Some(Err(error)) if error.kind() == io::ErrorKind::Other
+        ));
+        assert!(lines.next().is_none());
+
+        // the next iterator reads the final newline successfully
+        let lines = sub_cursor
+            .rlines()
+            .map(|line| line.map(|line| (line.get_start(), line.get_end())))
+            .collect::<io::Result<Vec<_>>>()?;
+        assert_eq!(lines, vec![(2, 3), (0, 1)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+use std::io::{self, Read, Seek, Write};
+
+use crate::endian::Endian::Little;
+use crate::SubCursor;
+
+const HEADER_LEN: usize = 8;
+const HEADER_SIZE: u64 = HEADER_LEN as u64;
+
+impl<T> SubCursor<T>
+where
+    T: Read + Seek,
+{
+    /// Returns an iterator over the RIFF chunks (like in WAV, AVI or WebP
+    /// files), that are directly inside of the [`SubCursor`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let mut data = b"RIFF\x18\0\0\0WAVE".to_vec();
+    /// data.extend_from_slice(b"fmt \x02\0\0\0ab");
+    /// data.extend_from_slice(b"data\x01\0\0\0c\0");
+    ///
+    /// let riff = SubCursor::from(data).riff_chunks().next().unwrap()?;
+    /// assert_eq!(riff.id(), *b"RIFF");
+    /// assert_eq!(riff.form_type(), Some(*b"WAVE"));
+    ///
+    /// let ids = riff
+    ///     .children()?
+    ///     .map(|chunk| chunk.map(|chunk| chunk.id()))
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    /// assert_eq!(ids, vec![*b"fmt ", *b"data"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// Chunks with an odd size are followed by a padding byte, which is not
+    /// part of the [`RiffChunk::data`].
+    pub fn riff_chunks(&self) -> RiffChunks<T> {
+        RiffChunks {
+            stack: vec![(self.sub_cursor(), 0)],
+            recursive: false,
+            depth: 0,
+        }
+    }
+
+    /// Returns an iterator over all RIFF chunks in the [`SubCursor`],
+    /// descending into `RIFF` and `LIST` containers. A container is returned
+    /// before the chunks inside of it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let mut data = b"RIFF\x18\0\0\0AVI ".to_vec();
+    /// data.extend_from_slice(b"LIST\x0c\0\0\0hdrl");
+    /// data.extend_from_slice(b"avih\0\0\0\0");
+    ///
+    /// let chunks = SubCursor::from(data)
+    ///     .riff_walk()
+    ///     .map(|chunk| chunk.map(|chunk| (chunk.id(), chunk.depth())))
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(chunks, vec![(*b"RIFF", 0), (*b"LIST", 1), (*b"avih", 2)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn riff_walk(&self) -> RiffChunks<T> {
+        RiffChunks {
+            recursive: true,
+            ..self.riff_chunks()
+        }
+    }
+}
+
+/// A chunk of a RIFF file, created by [`RiffChunks`].
+#[derive(Debug, Clone)]
+pub struct RiffChunk<T> {
+    id: [u8; 4],
+    form_type: Option<[u8; 4]>,
+    depth: usize,
+    size: u32,
+    data: SubCursor<T>,
+}
+
+impl<T> RiffChunk<T> {
+    /// Returns the four character code of the chunk.
+    pub const fn id(&self) -> [u8; 4] { self.id }
+
+    /// Returns the type of a `RIFF` or `LIST` container (like `WAVE`) or
+    /// `None`, if the chunk is not a container.
+    pub const fn form_type(&self) -> Option<[u8; 4]> { self.form_type }
+
+    /// Returns the size of the payload (without the padding byte).
+    pub const fn size(&self) -> u32 { self.size }
+
+    /// Returns the number of containers, that contain the chunk.
+    pub const fn depth(&self) -> usize { self.depth }
+
+    /// Returns the payload of the chunk, which includes the form type of a
+    /// container.
+    pub const fn data(&self) -> &SubCursor<T> { &self.data }
+}
+
+impl<T> RiffChunk<T>
+where
+    T: Read + Seek,
+{
+    /// Returns an iterator over the chunks inside of a `RIFF` or `LIST`
+    /// container.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the chunk is not a container.
+    pub fn children(&self) -> io::Result<RiffChunks<T>> {
+        let children = self
+            .form_type
+            .and_then(|_| self.data.child(4, u64::from(self.size()) - 4))
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "the chunk is not a container")
+            })?;
+
+        Ok(RiffChunks {
+            stack: vec![(children, 0)],
+            recursive: false,
+            depth: self.depth + 1,
+        })
+    }
+}
+
+impl<T> RiffChunk<T>
+where
+    T: Write + Seek,
+{
+    /// Overwrites the payload of the chunk in place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::Cursor;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let cursor = Arc::new(Mutex::new(Cursor::new(b"INFO\x03\0\0\0abc\0".to_vec())));
+    /// let sub_cursor = SubCursor::from(cursor.clone()).end(12);
+    ///
+    /// let chunk = sub_cursor.riff_chunks().next().unwrap()?;
+    /// chunk.rewrite(b"xyz")?;
+    /// assert_eq!(cursor.lock().unwrap().get_ref(), b"INFO\x03\0\0\0xyz\0");
+    ///
+    /// // the size of a chunk can not be changed
+    /// assert!(chunk.rewrite(b"wxyz").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if `payload` does not have the same size as
+    /// the chunk or if the [`SubCursor`] could not be written.
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    pub fn rewrite(&self, payload: &[u8]) -> io::Result<()> {
+        if payload.len() != self.data.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "the payload is {} bytes long, but the chunk is {} bytes long",
+                    payload.len(),
+                    self.data.len()
+                ),
+            ));
+        }
+
+        self.data.sub_cursor().write_all(payload)
+    }
+}
+
+/// An iterator over the chunks of a RIFF file, created by
+/// [`SubCursor::riff_chunks`] or [`SubCursor::riff_walk`].
+///
+/// [`SubCursor::riff_chunks`]: crate::SubCursor::riff_chunks
+/// [`SubCursor::riff_walk`]: crate::SubCursor::riff_walk
+#[derive(Debug, Clone)]
+pub struct RiffChunks<T> {
+    // the containers, that are currently walked, and the offset of the next
+    // chunk in them
+    stack: Vec<(SubCursor<T>, u64)>,
+    recursive: bool,
+    // the depth of the chunks in the first container
+    depth: usize,
+}
+
+impl<T> RiffChunks<T>
+where
+    T: Read + Seek,
+{
+    fn next_chunk(&mut self) -> io::Result<Option<RiffChunk<T>>> {
+        loop {
+            let depth = self.depth + self.stack.len().saturating_sub(1);
+            let Some((container, offset)) = self.stack.last_mut() else {
+                return Ok(None);
+            };
+
+            let len = container.len() as u64;
+            if *offset + HEADER_SIZE > len {
+                self.stack.pop();
+                continue;
+            }
+
+            let mut header = [0; HEADER_LEN];
+            container.read_exact_at(*offset, &mut header)?;
+
+            let id = [header[0], header[1], header[2], header[3]];
+            let chunk_size = Little.u32(&header[4..]);
+            let size = u64::from(chunk_size);
+
+            let data = container
+                .child(*offset + HEADER_SIZE, size)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("the chunk at {offset} is out of bounds"),
+                    )
+                })?;
+
+            let form_type = {
+                if (&id == b"RIFF" || &id == b"LIST") && size >= 4 {
+                    let mut form_type = [0; 4];
+                    data.read_exact_at(0, &mut form_type)?;
+                    Some(form_type)
+                } else {
+                    None
+                }
+            };
+
+            let chunk = RiffChunk {
+                id,
+                form_type,
+                depth,
+                size: chunk_size,
+                data,
+            };
+
+            // the padding byte might be missing at the end of the file
+            *offset += HEADER_SIZE + size + size % 2;
+
+            if self.recursive && form_type.is_some() {
+                if let Some(children) = chunk.data.child(4, size - 4) {
+                    self.stack.push((children, 0));
+                }
+            }
+
+            return Ok(Some(chunk));
+        }
+    }
+}
+
+impl<T> Iterator for RiffChunks<T>
+where
+    T: Read + Seek,
+{
+    type Item = io::Result<RiffChunk<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_chunk() {
+            Ok(chunk) => chunk.map(Ok),
+            Err(error) => {
+                self.stack.clear();
+                Some(Err(error))
+            }
+        }
+    }
+}
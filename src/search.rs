@@ -0,0 +1,412 @@
+use std::cmp;
+use std::io::{self, Read, Seek};
+
+use crate::SubCursor;
+
+/// The number of bytes, that are read at once while searching.
+pub const CHUNK_SIZE: usize = 8 * 1024;
+
+impl<T> SubCursor<T>
+where
+    T: Read + Seek,
+{
+    /// Returns the offset (relative to the start) of the first occurrence of
+    /// `needle` or `None` if there is no match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let sub_cursor = SubCursor::from(b"..PK..PK..".to_vec()).start(1);
+    ///
+    /// assert_eq!(sub_cursor.find(b"PK")?, Some(1));
+    /// assert_eq!(sub_cursor.find(b"ZIP")?, None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// The [`SubCursor`] is read in chunks, so the window does not have to fit
+    /// in memory. An empty `needle` matches at offset `0`.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the [`SubCursor`] could not be read.
+    pub fn find(&self, needle: &[u8]) -> io::Result<Option<usize>> {
+        self.find_from(0, needle, CHUNK_SIZE)
+    }
+
+    /// Returns the offset (relative to the start) of the last occurrence of
+    /// `needle` or `None` if there is no match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let sub_cursor = SubCursor::from(b"..PK..PK..".to_vec()).start(1);
+    ///
+    /// assert_eq!(sub_cursor.rfind(b"PK")?, Some(5));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// The [`SubCursor`] is read in chunks from the end, which makes this
+    /// function useful to find trailers (like the end of central directory of
+    /// a zip file).
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the [`SubCursor`] could not be read.
+    pub fn rfind(&self, needle: &[u8]) -> io::Result<Option<usize>> {
+        self.rfind_until(self.len(), needle, CHUNK_SIZE)
+    }
+
+    /// Returns an iterator over the offsets (relative to the start) of all
+    /// non-overlapping occurrences of `needle`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let sub_cursor = SubCursor::from(b"aaaaa".to_vec());
+    ///
+    /// assert_eq!(
+    ///     sub_cursor
+    ///         .find_iter(b"aa")
+    ///         .collect::<io::Result<Vec<_>>>()?,
+    ///     vec![0, 2]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_iter(&self, needle: &[u8]) -> FindIter<T> {
+        FindIter {
+            sub_cursor: self.sub_cursor(),
+            needle: needle.to_vec(),
+            offset: Some(0),
+            buffer: vec![],
+            buffer_start: 0,
+            chunk_size: CHUNK_SIZE,
+        }
+    }
+
+    /// Returns the first occurrence of `needle` as a [`SubCursor`] or `None`
+    /// if there is no match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let sub_cursor = SubCursor::from(b"..PK..PK..".to_vec()).start(1);
+    /// let found = sub_cursor.find_sub_cursor(b"PK")?.unwrap();
+    ///
+    /// assert_eq!((found.get_start(), found.get_end()), (2, 4));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the [`SubCursor`] could not be read.
+    pub fn find_sub_cursor(&self, needle: &[u8]) -> io::Result<Option<Self>> {
+        Ok(self
+            .find(needle)?
+            .and_then(|offset| self.child(offset as u64, needle.len() as u64)))
+    }
+
+    /// Reads up to `len` bytes starting at `offset` (relative to the start),
+    /// without changing the position of this [`SubCursor`].
+    pub(crate) fn read_range(&self, offset: usize, len: usize) -> io::Result<Vec<u8>> {
+        let len = cmp::min(len, self.len().saturating_sub(offset));
+        let mut result = Vec::with_capacity(len);
+
+        if let Some(mut child) = self.child(offset as u64, len as u64) {
+            child.read_to_end(&mut result)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Reads exactly `buf.len()` bytes starting at `offset` (relative to the
+    /// start), without changing the position of this [`SubCursor`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let sub_cursor = SubCursor::from(vec![1, 2, 3, 4, 5, 6]).start(2);
+    ///
+    /// let mut buffer = [0; 2];
+    /// sub_cursor.read_exact_at(1, &mut buffer)?;
+    ///
+    /// assert_eq!(buffer, [4, 5]);
+    /// assert_eq!(sub_cursor.position(), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the bytes are out of bounds or if the
+    /// underlying stream could not be read.
+    pub fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        let mut child = self.child(offset, buf.len() as u64).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "{} bytes at offset {} are out of bounds (length: {})",
+                    buf.len(),
+                    offset,
+                    self.len()
+                ),
+            )
+        })?;
+
+        child.read_exact(buf)
+    }
+
+    /// Searches forward for `needle`, starting at `offset`.
+    pub(crate) fn find_from(
+        &self,
+        offset: usize,
+        needle: &[u8],
+        chunk_size: usize,
+    ) -> io::Result<Option<usize>> {
+        let len = self.len();
+        if needle.is_empty() {
+            return Ok(Some(offset).filter(|offset| *offset <= len));
+        }
+
+        let mut position = offset;
+        while position + needle.len() <= len {
+            // the chunks overlap by `needle.len() - 1` bytes, so that a match,
+            // which straddles two chunks, is found
+            let buffer = self.read_range(position, chunk_size + needle.len() - 1)?;
+
+            if let Some(index) = buffer
+                .windows(needle.len())
+                .position(|bytes| bytes == needle)
+            {
+                return Ok(Some(position + index));
+            }
+
+            // the underlying stream is shorter than the window
+            if buffer.len() < needle.len() {
+                break;
+            }
+
+            position += chunk_size;
+        }
+
+        Ok(None)
+    }
+
+    /// Searches backward for a `needle`, that ends at or before `end`.
+    pub(crate) fn rfind_until(
+        &self,
+        end: usize,
+        needle: &[u8],
+        chunk_size: usize,
+    ) -> io::Result<Option<usize>> {
+        let end = cmp::min(end, self.len());
+        if needle.is_empty() {
+            return Ok(Some(end));
+        }
+
+        let mut end = end;
+        while end >= needle.len() {
+            let start = end.saturating_sub(chunk_size + needle.len() - 1);
+            let buffer = self.read_range(start, end - start)?;
+
+            if let Some(index) = buffer
+                .windows(needle.len())
+                .rposition(|bytes| bytes == needle)
+            {
+                return Ok(Some(start + index));
+            }
+
+            if start == 0 {
+                break;
+            }
+
+            end = start + needle.len() - 1;
+        }
+
+        Ok(None)
+    }
+}
+
+/// An iterator over the offsets of all occurrences of a needle in a
+/// [`SubCursor`], created by [`SubCursor::find_iter`].
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`SubCursor::find_iter`]: crate::SubCursor::find_iter
+#[derive(Debug, Clone)]
+pub struct FindIter<T> {
+    sub_cursor: SubCursor<T>,
+    needle: Vec<u8>,
+    // `None`, if the iterator is exhausted
+    offset: Option<usize>,
+    // the data, that has been read, but not searched yet (like the buffer of
+    // a `BufReader`), so every byte is only read once
+    buffer: Vec<u8>,
+    // the offset of the first byte of the buffer
+    buffer_start: usize,
+    chunk_size: usize,
+}
+
+impl<T> FindIter<T>
+where
+    T: Read + Seek,
+{
+    /// Returns the byte at `offset`, which is usually still in the buffer.
+    pub(crate) fn byte_at(&self, offset: usize) -> io::Result<Option<u8>> {
+        if let Some(byte) = offset
+            .checked_sub(self.buffer_start)
+            .and_then(|index| self.buffer.get(index))
+        {
+            return Ok(Some(*byte));
+        }
+
+        Ok(self.sub_cursor.read_range(offset, 1)?.first().copied())
+    }
+
+    /// Searches forward for the needle, starting at `offset`.
+    fn find_next(&mut self, offset: usize) -> io::Result<Option<usize>> {
+        let len = self.sub_cursor.len();
+        let needle_len = self.needle.len();
+        if needle_len == 0 {
+            return Ok(Some(offset).filter(|offset| *offset <= len));
+        }
+
+        let buffer_end = self.buffer_start + self.buffer.len();
+        if offset < self.buffer_start || offset > buffer_end {
+            self.buffer.clear();
+            self.buffer_start = offset;
+        }
+
+        // the data before `offset` has already been searched
+        let mut from = offset - self.buffer_start;
+        loop {
+            if let Some(index) = self.buffer[from..]
+                .windows(needle_len)
+                .position(|bytes| bytes == &self.needle[..])
+            {
+                return Ok(Some(self.buffer_start + from + index));
+            }
+
+            let buffer_end = self.buffer_start + self.buffer.len();
+            if buffer_end >= len {
+                return Ok(None);
+            }
+
+            // keep the last `needle.len() - 1` bytes, because a match might
+            // straddle two chunks
+            let keep = cmp::max(from, self.buffer.len().saturating_sub(needle_len - 1));
+            self.buffer.drain(..keep);
+            self.buffer_start += keep;
+            from = 0;
+
+            let chunk = self.sub_cursor.read_range(buffer_end, self.chunk_size)?;
+
+            // the underlying stream is shorter than the window
+            if chunk.is_empty() {
+                return Ok(None);
+            }
+
+            self.buffer.extend_from_slice(&chunk);
+        }
+    }
+}
+
+impl<T> Iterator for FindIter<T>
+where
+    T: Read + Seek,
+{
+    type Item = io::Result<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset?;
+
+        match self.find_next(offset) {
+            Ok(Some(found)) => {
+                // an empty needle matches at every offset
+                self.offset = Some(found + cmp::max(self.needle.len(), 1));
+                Some(Ok(found))
+            }
+            Ok(None) => {
+                self.offset = None;
+                None
+            }
+            Err(error) => {
+                self.offset = None;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_chunk_boundaries() -> io::Result<()> {
+        let sub_cursor = SubCursor::from(b"#abcdefghij#abc".to_vec()).start(1);
+
+        for chunk_size in 1..16 {
+            for (needle, first, last) in &[
+                (&b"abc"[..], Some(0), Some(11)),
+                (&b"def"[..], Some(3), Some(3)),
+                (&b"j#a"[..], Some(9), Some(9)),
+                (&b"bc"[..], Some(1), Some(12)),
+                (&b"#abcdefghij#abc"[..], None, None),
+                (&b"xyz"[..], None, None),
+            ] {
+                assert_eq!(sub_cursor.find_from(0, needle, chunk_size)?, *first);
+                assert_eq!(
+                    sub_cursor.rfind_until(sub_cursor.len(), needle, chunk_size)?,
+                    *last
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_iter_chunk_boundaries() -> io::Result<()> {
+        let sub_cursor = SubCursor::from(b"#a\nbc\n\ndef\r\n".to_vec()).start(1);
+
+        for chunk_size in 1..16 {
+            for (needle, expected) in &[
+                (&b"\n"[..], vec![1, 4, 5, 10]),
+                (&b"\r\n"[..], vec![9]),
+                (&b"bc\n\nd"[..], vec![2]),
+            ] {
+                let find_iter = FindIter {
+                    chunk_size,
+                    ..sub_cursor.find_iter(needle)
+                };
+
+                assert_eq!(find_iter.collect::<io::Result<Vec<_>>>()?, *expected);
+            }
+        }
+
+        Ok(())
+    }
+}
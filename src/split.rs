@@ -0,0 +1,199 @@
+use std::io::{self, Read, Seek};
+
+use crate::{FindIter, SubCursor};
+
+impl<T> SubCursor<T>
+where
+    T: Read + Seek,
+{
+    /// Returns an iterator over the parts of the [`SubCursor`], that are
+    /// separated by `delimiter`. Every part is returned as a [`SubCursor`],
+    /// pointing into the same stream, so no data is copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// let sub_cursor = SubCursor::from(b"a||bc||".to_vec());
+    ///
+    /// let parts = sub_cursor
+    ///     .split(b"||")
+    ///     .map(|part| part.map(|part| (part.get_start(), part.get_end())))
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(parts, vec![(0, 1), (3, 5), (7, 7)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// Like [`slice::split`], an empty part is returned, if the
+    /// [`SubCursor`] ends with the `delimiter`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delimiter` is empty.
+    ///
+    /// [`slice::split`]: https://doc.rust-lang.org/std/primitive.slice.html#method.split
+    pub fn split(&self, delimiter: &[u8]) -> Split<T> {
+        assert!(!delimiter.is_empty(), "the delimiter must not be empty");
+
+        Split {
+            sub_cursor: self.sub_cursor(),
+            matches: self.find_iter(delimiter),
+            delimiter_len: delimiter.len(),
+            offset: Some(0),
+            trailing: true,
+        }
+    }
+
+    /// Returns an iterator over the lines of the [`SubCursor`]. Every line is
+    /// returned as a [`SubCursor`], pointing into the same stream, so no data
+    /// is copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::Read;
+    ///
+    /// let sub_cursor = SubCursor::from(b"first\r\nsecond\n".to_vec());
+    ///
+    /// let mut lines = vec![];
+    /// for line in sub_cursor.lines() {
+    ///     let mut buffer = String::new();
+    ///     line?.read_to_string(&mut buffer)?;
+    ///     lines.push(buffer);
+    /// }
+    ///
+    /// assert_eq!(lines, vec!["first".to_string(), "second".to_string()]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// Like [`BufRead::lines`], the lines do not contain the newline (`\n`)
+    /// or `\r\n` and there is no empty line after a final newline.
+    ///
+    /// [`BufRead::lines`]: std::io::BufRead::lines
+    pub fn lines(&self) -> Lines<T> {
+        Lines {
+            split: Split {
+                trailing: false,
+                ..self.split(b"\n")
+            },
+        }
+    }
+}
+
+/// An iterator over the parts of a [`SubCursor`], that are separated by a
+/// delimiter, created by [`SubCursor::split`].
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`SubCursor::split`]: crate::SubCursor::split
+#[derive(Debug, Clone)]
+pub struct Split<T> {
+    sub_cursor: SubCursor<T>,
+    matches: FindIter<T>,
+    delimiter_len: usize,
+    // the start of the next part or `None`, if the iterator is exhausted
+    offset: Option<usize>,
+    // whether an empty part after the last delimiter should be returned
+    trailing: bool,
+}
+
+impl<T> Split<T>
+where
+    T: Read + Seek,
+{
+    /// Returns the start and the end of the next part.
+    fn next_range(&mut self) -> Option<io::Result<(usize, usize)>> {
+        let offset = self.offset?;
+
+        let end = match self.matches.next() {
+            Some(Ok(found)) => {
+                self.offset = Some(found + self.delimiter_len);
+                found
+            }
+            Some(Err(error)) => {
+                self.offset = None;
+                return Some(Err(error));
+            }
+            None => {
+                self.offset = None;
+
+                let len = self.sub_cursor.len();
+                if offset == len && !self.trailing {
+                    return None;
+                }
+
+                len
+            }
+        };
+
+        Some(Ok((offset, end)))
+    }
+}
+
+impl<T> Iterator for Split<T>
+where
+    T: Read + Seek,
+{
+    type Item = io::Result<SubCursor<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (offset, end) = match self.next_range()? {
+            Ok(range) => range,
+            Err(error) => return Some(Err(error)),
+        };
+
+        self.sub_cursor
+            .child(offset as u64, (end - offset) as u64)
+            .map(Ok)
+    }
+}
+
+/// An iterator over the lines of a [`SubCursor`], created by
+/// [`SubCursor::lines`].
+///
+/// [`SubCursor`]: crate::SubCursor
+/// [`SubCursor::lines`]: crate::SubCursor::lines
+#[derive(Debug, Clone)]
+pub struct Lines<T> {
+    split: Split<T>,
+}
+
+impl<T> Iterator for Lines<T>
+where
+    T: Read + Seek,
+{
+    type Item = io::Result<SubCursor<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (offset, mut end) = match self.split.next_range()? {
+            Ok(range) => range,
+            Err(error) => return Some(Err(error)),
+        };
+
+        // remove the carriage return of a `\r\n` line ending, which is still
+        // in the buffer of the search
+        if end > offset {
+            match self.split.matches.byte_at(end - 1) {
+                Ok(Some(b'\r')) => end -= 1,
+                Ok(_) => {}
+                Err(error) => return Some(Err(error)),
+            }
+        }
+
+        self.split
+            .sub_cursor
+            .child(offset as u64, (end - offset) as u64)
+            .map(Ok)
+    }
+}
@@ -1,11 +1,16 @@
 // Other library, that does almost the same
 // https://github.com/hinaria/slice/
 use std::cmp;
+use std::convert::TryFrom;
 use std::fmt;
 use std::io::{self, Cursor};
 use std::io::{Read, Seek, SeekFrom, Write};
+use std::iter;
 
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::recorder::{Origin, OriginGuard, Recorder};
+use crate::registry::{Edit, Registration, Registry};
 
 /// A [`SubCursor`] allows to only have access to parts of the underlying
 /// [`Read`]er or [`Write`]r.
@@ -48,6 +53,8 @@ pub struct SubCursor<T> {
     end: usize,
     position: u64,
     preserve: bool,
+    registry: Option<Registration>,
+    origin: Option<Origin>,
 }
 
 #[allow(dead_code)]
@@ -67,8 +74,99 @@ impl SubCursor<Cursor<Vec<u8>>> {
             end: 0,
             position: 0,
             preserve: false,
+            registry: None,
+            origin: None,
         }
     }
+
+    /// Resizes the [`SubCursor`] to `new_len` bytes, by inserting or removing
+    /// bytes at the end of the [`SubCursor`]. All bytes after the end will be
+    /// moved accordingly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::Cursor;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let cursor = Arc::new(Mutex::new(Cursor::new(b"aaabbbccc".to_vec())));
+    /// let mut sub_cursor = SubCursor::from(cursor.clone()).start(3).end(6);
+    ///
+    /// // remove one byte
+    /// sub_cursor.resize(2);
+    /// assert_eq!(cursor.lock().unwrap().get_ref(), b"aaabbccc");
+    ///
+    /// // insert three bytes
+    /// sub_cursor.resize(5);
+    /// assert_eq!(cursor.lock().unwrap().get_ref(), b"aaabb\0\0\0ccc");
+    /// assert_eq!(sub_cursor.len(), 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// Inserted bytes are zeroed. Other [`SubCursor`]s on the same buffer will
+    /// only be adjusted, if they share a [`Registry`] with this [`SubCursor`].
+    ///
+    /// [`Registry`]: crate::Registry
+    pub fn resize(&mut self, new_len: usize) {
+        // the lock is held until the edit has been recorded, so concurrent
+        // resizes are recorded in the order they are applied and reads and
+        // writes (which sync while holding the lock) never see a moved buffer
+        // with the old bounds
+        let cursor = self.cursor.clone();
+        let mut cursor = cursor.lock().unwrap_or_else(PoisonError::into_inner);
+
+        self.sync();
+
+        let old_len = self.len();
+        if new_len == old_len {
+            return;
+        }
+
+        let edit = {
+            if new_len > old_len {
+                Edit {
+                    at: self.end,
+                    removed: 0,
+                    inserted: new_len - old_len,
+                }
+            } else {
+                Edit {
+                    at: self.start + new_len,
+                    removed: old_len - new_len,
+                    inserted: 0,
+                }
+            }
+        };
+
+        let position = usize::try_from(cursor.position()).unwrap_or(usize::MAX);
+        let buffer = cursor.get_mut();
+
+        // the end might be located after the end of the buffer:
+        if buffer.len() < self.end {
+            buffer.resize(self.end, 0);
+        }
+
+        buffer.splice(
+            edit.at..edit.at + edit.removed,
+            iter::repeat_n(0, edit.inserted),
+        );
+
+        self.end = self.start + new_len;
+        self.position = cmp::min(self.position, self.end as u64);
+
+        if let Some(registry) = &mut self.registry {
+            registry.push(edit);
+        }
+
+        // the underlying cursor should keep pointing at the same data
+        cursor.set_position(edit.map(position) as u64);
+    }
 }
 
 #[allow(dead_code)]
@@ -104,13 +202,17 @@ impl<T> SubCursor<T> {
     // with a start, that's bigger than the end? like this
     // SubCursor::new().start(7).end(3)? this should cause a panic!
     pub fn start(&self, value: usize) -> Self {
+        let (_, end, _, generation) = self.bounds();
+
         Self {
             // very cheap to clone:
             cursor: self.cursor.clone(),
             start: value,
             position: value as u64,
-            end: self.end,
+            end,
             preserve: self.preserve,
+            registry: self.registration(generation),
+            origin: self.origin.clone(),
         }
     }
 
@@ -143,13 +245,17 @@ impl<T> SubCursor<T> {
     /// an invalid [`SubCursor`]. This function won't check for validity of the
     /// end value.
     pub fn end(&self, value: usize) -> Self {
+        let (start, _, _, generation) = self.bounds();
+
         Self {
             // very cheap to clone:
             cursor: self.cursor.clone(),
-            start: self.start,
-            position: self.start as u64,
+            start,
+            position: start as u64,
             end: value,
             preserve: self.preserve,
+            registry: self.registration(generation),
+            origin: self.origin.clone(),
         }
     }
 
@@ -192,13 +298,17 @@ impl<T> SubCursor<T> {
     /// The preserve option is enabled by default and should be disabled, if
     /// seek operations of the underlying cursor take a long time.
     pub fn preserve(&self, value: bool) -> Self {
+        let (start, end, position, generation) = self.bounds();
+
         Self {
             // very cheap to clone:
             cursor: self.cursor.clone(),
-            start: self.start,
-            position: self.position,
-            end: self.end,
+            start,
+            position,
+            end,
             preserve: value,
+            registry: self.registration(generation),
+            origin: self.origin.clone(),
         }
     }
 
@@ -283,6 +393,7 @@ impl<T> SubCursor<T> {
     /// ```
     #[inline]
     pub fn set_position(&mut self, pos: u64) {
+        self.sync();
         self.position = pos.checked_rem(self.len() as u64).unwrap_or(pos) + self.start as u64
     }
 
@@ -304,15 +415,49 @@ impl<T> SubCursor<T> {
     /// [`start`]: #method.start
     /// [`preserve`]: #method.preserve
     pub fn sub_cursor(&self) -> Self {
+        let (start, end, _, generation) = self.bounds();
+
         Self {
             cursor: self.cursor.clone(),
-            start: self.start,
-            end: self.end,
-            position: self.start as u64,
+            start,
+            end,
+            position: start as u64,
             preserve: self.preserve,
+            registry: self.registration(generation),
+            origin: self.origin.clone(),
         }
     }
 
+    /// Creates a new [`SubCursor`], that covers `len` bytes starting at
+    /// `offset` (relative to the start of this [`SubCursor`]).
+    ///
+    /// Returns `None`, if the child would not be inside of this
+    /// [`SubCursor`].
+    ///
+    /// The child uses the same bounds as [`len`] (the ones of the last
+    /// [`sync`]) and applies the resizes, that happened since then, when it is
+    /// used.
+    ///
+    /// [`len`]: #method.len
+    /// [`sync`]: #method.sync
+    pub(crate) fn child(&self, offset: u64, len: u64) -> Option<Self> {
+        let offset = usize::try_from(offset).ok()?;
+        let end = offset.checked_add(usize::try_from(len).ok()?)?;
+        if end > self.len() {
+            return None;
+        }
+
+        Some(Self {
+            cursor: self.cursor.clone(),
+            start: self.start + offset,
+            end: self.start + end,
+            position: (self.start + offset) as u64,
+            preserve: self.preserve,
+            registry: self.registry.clone(),
+            origin: self.origin.clone(),
+        })
+    }
+
     /// Consumes this cursor, returning the underlying value.
     ///
     /// # Example
@@ -370,6 +515,213 @@ impl<T> SubCursor<T> {
     /// [`end`]: #method.end
     #[inline]
     pub const fn get_end(&self) -> usize { self.end }
+
+    /// Registers this [`SubCursor`] with a [`Registry`], so it will follow
+    /// resizes of other [`SubCursor`]s, that are registered with the same
+    /// [`Registry`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{Registry, SubCursor};
+    /// let registry = Registry::new();
+    /// let mut first = SubCursor::from(vec![1, 2, 3, 4, 5, 6])
+    ///     .end(3)
+    ///     .registry(&registry);
+    /// let mut second = first.start(3).end(6);
+    ///
+    /// // shrink the first SubCursor by one byte
+    /// first.resize(2);
+    ///
+    /// second.sync();
+    /// assert_eq!(second.get_start(), 2);
+    /// assert_eq!(second.get_end(), 5);
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// [`SubCursor`]s created from a registered [`SubCursor`] are registered
+    /// too.
+    ///
+    /// [`Registry`]: crate::Registry
+    #[must_use]
+    pub fn registry(&self, value: &Registry) -> Self {
+        let (start, end, position, _) = self.bounds();
+
+        Self {
+            cursor: self.cursor.clone(),
+            start,
+            end,
+            position,
+            preserve: self.preserve,
+            registry: Some(value.register()),
+            origin: self.origin.clone(),
+        }
+    }
+
+    /// Returns the `start`, `end`, absolute position and generation, after
+    /// all resizes from the registry have been applied.
+    pub(crate) fn bounds(&self) -> (usize, usize, u64, usize) {
+        self.registry
+            .as_ref()
+            .map_or((self.start, self.end, self.position, 0), |registry| {
+                registry.apply(self.start, self.end, self.position)
+            })
+    }
+
+    /// Registers a new [`SubCursor`] at `generation` with the [`Registry`] of
+    /// this one.
+    ///
+    /// [`Registry`]: crate::Registry
+    fn registration(&self, generation: usize) -> Option<Registration> {
+        self.registry
+            .as_ref()
+            .map(|registry| registry.at(generation))
+    }
+
+    /// Applies all resizes of the [`Registry`], that happened since this
+    /// [`SubCursor`] has been used the last time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{Registry, SubCursor};
+    /// let registry = Registry::new();
+    /// let root = SubCursor::from(vec![1, 2, 3, 4, 5, 6]).registry(&registry);
+    ///
+    /// let mut first = root.end(3);
+    /// let mut second = root.start(3);
+    ///
+    /// first.resize(5);
+    /// assert_eq!(second.get_start(), 3);
+    ///
+    /// second.sync();
+    /// assert_eq!(second.get_start(), 5);
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// Reads, writes and seeks call this function automatically. It is only
+    /// needed before calling [`len`], [`position`], [`get_start`] or
+    /// [`get_end`], which return the values as of the last use.
+    ///
+    /// [`Registry`]: crate::Registry
+    /// [`len`]: #method.len
+    /// [`position`]: #method.position
+    /// [`get_start`]: #method.get_start
+    /// [`get_end`]: #method.get_end
+    pub fn sync(&mut self) {
+        let (start, end, position, generation) = self.bounds();
+
+        self.start = start;
+        self.end = end;
+        self.position = position;
+        if let Some(registry) = &mut self.registry {
+            registry.advance(generation);
+        }
+    }
+
+    /// Reports the window of this [`SubCursor`] to the [`Recorder`], until the
+    /// returned guard is dropped.
+    ///
+    /// [`Recorder`]: crate::Recorder
+    fn enter(&self) -> Option<OriginGuard> {
+        self.origin
+            .as_ref()
+            .map(|origin| origin.enter(self.start as u64..self.end as u64))
+    }
+}
+
+impl<U> SubCursor<Recorder<U>> {
+    /// Reports the window of this [`SubCursor`] to the [`Recorder`], so every
+    /// recorded [`Event`] tells, which window issued it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{Event, Recorder, SubCursor};
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::{Cursor, Read};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let recorder = Arc::new(Mutex::new(Recorder::new(Cursor::new(vec![0; 8]))));
+    /// let mut sub_cursor = SubCursor::from(recorder.clone()).start(2).end(6).recorded();
+    ///
+    /// sub_cursor.read_exact(&mut [0; 4])?;
+    ///
+    /// let recorder = recorder.lock().unwrap();
+    /// assert_eq!(recorder.events()[0].window(), Some(&(2..6)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// [`SubCursor`]s created from a recorded [`SubCursor`] are recorded too.
+    ///
+    /// [`Recorder`]: crate::Recorder
+    /// [`Event`]: crate::Event
+    #[must_use]
+    pub fn recorded(&self) -> Self {
+        let origin = self
+            .cursor
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .origin()
+            .clone();
+
+        let (start, end, position, generation) = self.bounds();
+
+        Self {
+            cursor: self.cursor.clone(),
+            start,
+            end,
+            position,
+            preserve: self.preserve,
+            registry: self.registration(generation),
+            origin: Some(origin),
+        }
+    }
+}
+
+impl<T> SubCursor<T>
+where
+    T: Seek,
+{
+    /// Locks the underlying stream and calls `f` with it and the absolute
+    /// start of this [`SubCursor`]. The position of the underlying stream
+    /// will be restored afterwards, if [`preserve`] is enabled.
+    ///
+    /// [`preserve`]: #method.preserve
+    pub(crate) fn with_inner<R>(
+        &mut self,
+        f: impl FnOnce(&mut T, u64) -> io::Result<R>,
+    ) -> io::Result<R> {
+        // the bounds are synced while the stream is locked, see `read`
+        let cursor = self.cursor.clone();
+        let mut cursor = cursor.lock().unwrap_or_else(PoisonError::into_inner);
+        self.sync();
+
+        let origin = self.enter();
+        let position = {
+            if self.preserve {
+                Some(cursor.stream_position()?)
+            } else {
+                None
+            }
+        };
+
+        let result = f(&mut cursor, self.start as u64);
+
+        if let Some(position) = position {
+            cursor.seek(SeekFrom::Start(position))?;
+        }
+        drop(origin);
+        drop(cursor);
+
+        result
+    }
 }
 
 impl<T> Seek for SubCursor<T>
@@ -403,6 +755,8 @@ where
     /// # }
     /// ```
     fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        self.sync();
+
         let mut relative_position = self.position();
 
         // early return, because if the length is 0, there is nothing to seek...
@@ -465,6 +819,12 @@ where
     T: Read + Seek,
 {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // the bounds are synced while the stream is locked, so a resize can
+        // not happen between the sync and the read
+        let cursor = self.cursor.clone();
+        let mut cursor = cursor.lock().unwrap_or_else(PoisonError::into_inner);
+        self.sync();
+
         // there is nothing to read after the self.end.
         if self.position >= self.end as u64 {
             Ok(0)
@@ -472,31 +832,30 @@ where
             // check how many bytes are available:
             let remaining = cmp::min(buf.len() as u64, self.end as u64 - self.position);
 
+            // tell a recorder, which window accesses the stream
+            let origin = self.enter();
+
+            // remember old position, if preserve is enabled
             let position = {
-                let mut cursor = self.cursor.lock().unwrap();
-                cursor.stream_position()?
+                if self.preserve {
+                    Some(cursor.stream_position()?)
+                } else {
+                    None
+                }
             };
 
             // seek to the current position
-            {
-                let mut cursor = self.cursor.lock().unwrap();
-                cursor.seek(SeekFrom::Start(self.position as u64))?;
-            }
+            cursor.seek(SeekFrom::Start(self.position))?;
 
             // result is the number of bytes, that have been read
-            let result = {
-                let mut cursor = self.cursor.lock().unwrap();
-                cursor.by_ref().take(remaining).read(buf)?
-            };
+            let result = cursor.by_ref().take(remaining).read(buf)?;
 
-            // seek back to the old position, if preserve is enabled
-            if self.preserve {
+            if let Some(position) = position {
                 // seek to the old position
-                {
-                    let mut cursor = self.cursor.lock().unwrap();
-                    cursor.seek(SeekFrom::Start(position))?;
-                }
+                cursor.seek(SeekFrom::Start(position))?;
             }
+            drop(origin);
+            drop(cursor);
 
             // update the new absolute position
             self.position += result as u64;
@@ -511,6 +870,12 @@ where
     T: Write + Seek,
 {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // the bounds are synced while the stream is locked, so a resize can
+        // not happen between the sync and the write
+        let cursor = self.cursor.clone();
+        let mut cursor = cursor.lock().unwrap_or_else(PoisonError::into_inner);
+        self.sync();
+
         // skip if the cursor is at the EOF
         if self.position >= self.end as u64 {
             return Ok(0);
@@ -518,35 +883,35 @@ where
 
         // check how many bytes are available:
         let remaining = cmp::min(buf.len() as u64, self.end as u64 - self.position);
-        let remaining = cmp::min(remaining, usize::max_value() as u64) as usize;
+        let remaining = usize::try_from(remaining).unwrap_or(usize::MAX);
+
+        // tell a recorder, which window accesses the stream
+        let origin = self.enter();
 
+        // remember old position:
         let position = {
             if self.preserve {
-                // remember old position:
-                {
-                    Some(self.cursor.lock().unwrap().stream_position()?)
-                }
+                Some(cursor.stream_position()?)
             } else {
                 None
             }
         };
 
         // seek to the current position
-        {
-            let mut cursor = self.cursor.lock().unwrap();
-            cursor.seek(SeekFrom::Start(self.position as u64))?;
-        }
+        cursor.seek(SeekFrom::Start(self.position))?;
 
         // write as many bytes as possible in the buffer
-        let result = { self.cursor.lock().unwrap().write(&buf[..remaining])? };
+        let result = cursor.write(&buf[..remaining])?;
 
         if let Some(position) = position {
             // seek to the old position
-            {
-                let mut cursor = self.cursor.lock().unwrap();
-                cursor.seek(SeekFrom::Start(position))?;
-            }
+            cursor.seek(SeekFrom::Start(position))?;
         }
+        drop(origin);
+        drop(cursor);
+
+        // update the new absolute position
+        self.position += result as u64;
 
         Ok(result)
     }
@@ -554,7 +919,10 @@ where
     fn flush(&mut self) -> io::Result<()> {
         // flush the underlying writer
         {
-            self.cursor.lock().unwrap().flush()?;
+            self.cursor
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .flush()?;
         }
         Ok(())
     }
@@ -591,6 +959,8 @@ impl<T: Seek> From<T> for SubCursor<T> {
             cursor: Arc::new(Mutex::new(value)),
             position: 0,
             preserve: true,
+            registry: None,
+            origin: None,
         }
     }
 }
@@ -603,6 +973,8 @@ impl From<Vec<u8>> for SubCursor<Cursor<Vec<u8>>> {
             cursor: Arc::new(Mutex::new(Cursor::new(value))),
             position: 0,
             preserve: true,
+            registry: None,
+            origin: None,
         }
     }
 }
@@ -616,6 +988,8 @@ impl<T> From<Mutex<T>> for SubCursor<T> {
             cursor: Arc::new(value),
             position: 0,
             preserve: true,
+            registry: None,
+            origin: None,
         }
     }
 }
@@ -629,6 +1003,8 @@ impl<T> From<Arc<Mutex<T>>> for SubCursor<T> {
             end: 0,
             position: 0,
             preserve: true,
+            registry: None,
+            origin: None,
         }
     }
 }
@@ -0,0 +1,810 @@
+use std::cmp;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek, Write};
+use std::str;
+
+use crate::SubCursor;
+
+const BLOCK_LEN: usize = 512;
+const BLOCK_SIZE: u64 = BLOCK_LEN as u64;
+
+/// The type of a [`TarHeader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntryType {
+    /// A regular file.
+    Regular,
+    /// A hard link to another entry.
+    Link,
+    /// A symbolic link.
+    Symlink,
+    /// A character device.
+    Char,
+    /// A block device.
+    Block,
+    /// A directory.
+    Directory,
+    /// A named pipe.
+    Fifo,
+    /// Any other type.
+    Other(u8),
+}
+
+impl EntryType {
+    const fn from_byte(value: u8) -> Self {
+        match value {
+            b'0' | b'\0' | b'7' => Self::Regular,
+            b'1' => Self::Link,
+            b'2' => Self::Symlink,
+            b'3' => Self::Char,
+            b'4' => Self::Block,
+            b'5' => Self::Directory,
+            b'6' => Self::Fifo,
+            value => Self::Other(value),
+        }
+    }
+
+    const fn as_byte(self) -> u8 {
+        match self {
+            Self::Regular => b'0',
+            Self::Link => b'1',
+            Self::Symlink => b'2',
+            Self::Char => b'3',
+            Self::Block => b'4',
+            Self::Directory => b'5',
+            Self::Fifo => b'6',
+            Self::Other(value) => value,
+        }
+    }
+}
+
+/// The metadata of a member of a tar archive.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{EntryType, TarHeader};
+/// let header = TarHeader::new("assets/logo.png")
+///     .mode(0o600)
+///     .mtime(1_500_000_000);
+///
+/// assert_eq!(header.get_path(), "assets/logo.png");
+/// assert_eq!(header.get_kind(), EntryType::Regular);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TarHeader {
+    path: String,
+    link_name: String,
+    kind: EntryType,
+    mode: u32,
+    uid: u64,
+    gid: u64,
+    mtime: u64,
+    uname: String,
+    gname: String,
+}
+
+impl TarHeader {
+    /// Creates a new [`TarHeader`] for a regular file with the mode `0o644`.
+    pub fn new<S: Into<String>>(path: S) -> Self {
+        Self {
+            path: path.into(),
+            link_name: String::new(),
+            kind: EntryType::Regular,
+            mode: 0o644,
+            uid: 0,
+            gid: 0,
+            mtime: 0,
+            uname: String::new(),
+            gname: String::new(),
+        }
+    }
+
+    /// Sets the type of the entry.
+    #[must_use]
+    pub fn kind(&self, value: EntryType) -> Self {
+        Self {
+            kind: value,
+            ..self.clone()
+        }
+    }
+
+    /// Sets the target of a link.
+    #[must_use]
+    pub fn link_name<S: Into<String>>(&self, value: S) -> Self {
+        Self {
+            link_name: value.into(),
+            ..self.clone()
+        }
+    }
+
+    /// Sets the permissions of the entry.
+    #[must_use]
+    pub fn mode(&self, value: u32) -> Self {
+        Self {
+            mode: value,
+            ..self.clone()
+        }
+    }
+
+    /// Sets the user and group id of the owner.
+    #[must_use]
+    pub fn owner(&self, uid: u64, gid: u64) -> Self {
+        Self {
+            uid,
+            gid,
+            ..self.clone()
+        }
+    }
+
+    /// Sets the modification time (seconds since the unix epoch).
+    #[must_use]
+    pub fn mtime(&self, value: u64) -> Self {
+        Self {
+            mtime: value,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the path of the entry.
+    pub fn get_path(&self) -> &str { &self.path }
+
+    /// Returns the target of a link.
+    pub fn get_link_name(&self) -> &str { &self.link_name }
+
+    /// Returns the type of the entry.
+    pub const fn get_kind(&self) -> EntryType { self.kind }
+
+    /// Returns the permissions of the entry.
+    pub const fn get_mode(&self) -> u32 { self.mode }
+
+    /// Returns the user id of the owner.
+    pub const fn get_uid(&self) -> u64 { self.uid }
+
+    /// Returns the group id of the owner.
+    pub const fn get_gid(&self) -> u64 { self.gid }
+
+    /// Returns the modification time (seconds since the unix epoch).
+    pub const fn get_mtime(&self) -> u64 { self.mtime }
+
+    /// Returns the user name of the owner.
+    pub fn get_uname(&self) -> &str { &self.uname }
+
+    /// Returns the group name of the owner.
+    pub fn get_gname(&self) -> &str { &self.gname }
+}
+
+/// A member of a tar archive and its location in the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TarEntry {
+    header: TarHeader,
+    header_offset: u64,
+    data_offset: u64,
+    size: u64,
+}
+
+impl TarEntry {
+    /// Returns the metadata of the entry.
+    pub const fn header(&self) -> &TarHeader { &self.header }
+
+    /// Returns the offset of the (first) header of the entry. Long names are
+    /// stored in extra headers in front of the actual header.
+    pub const fn header_offset(&self) -> u64 { self.header_offset }
+
+    /// Returns the offset of the data of the entry.
+    pub const fn data_offset(&self) -> u64 { self.data_offset }
+
+    /// Returns the size of the data of the entry.
+    pub const fn size(&self) -> u64 { self.size }
+}
+
+/// A [`TarArchive`] lists the members of a tar file, that is located in a
+/// [`SubCursor`].
+///
+/// The ustar, GNU (long names and base-256 numbers) and PAX formats are
+/// supported.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{SubCursor, TarArchive, TarHeader, TarWriter};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::Read;
+///
+/// let mut writer = TarWriter::new(SubCursor::new());
+/// writer.append(&TarHeader::new("hello.txt"), &b"Hello World!"[..])?;
+/// let sub_cursor = writer.finish()?;
+///
+/// let archive = TarArchive::new(sub_cursor)?;
+/// let entry = &archive.entries()[0];
+/// assert_eq!(entry.header().get_path(), "hello.txt");
+///
+/// let mut result = String::new();
+/// archive.data(entry)?.read_to_string(&mut result)?;
+/// assert_eq!(result, "Hello World!".to_string());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TarArchive<T> {
+    sub_cursor: SubCursor<T>,
+    entries: Vec<TarEntry>,
+}
+
+impl<T> TarArchive<T>
+where
+    T: Read + Seek,
+{
+    /// Reads all headers of the tar file in the [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if a header is invalid or if the
+    /// [`SubCursor`] could not be read.
+    pub fn new(sub_cursor: SubCursor<T>) -> io::Result<Self> {
+        let len = sub_cursor.len() as u64;
+
+        let mut entries = vec![];
+        let mut global = HashMap::new();
+        let mut extended = HashMap::new();
+        let mut long_name = None;
+        let mut long_link_name = None;
+        let mut first_header = None;
+
+        let mut offset = 0;
+        while offset + BLOCK_SIZE <= len {
+            let mut block = [0; BLOCK_LEN];
+            sub_cursor.read_exact_at(offset, &mut block)?;
+
+            // the archive ends with (two) empty blocks
+            if block.iter().all(|byte| *byte == 0) {
+                break;
+            }
+
+            if checksum(&block) != parse_number(&block[148..156])? {
+                return Err(invalid_data(&format!(
+                    "invalid checksum of the header at {offset}"
+                )));
+            }
+
+            let header_offset = *first_header.get_or_insert(offset);
+            let size = parse_number(&block[124..136])?;
+            let data_offset = offset + BLOCK_SIZE;
+            offset = entry_end(data_offset, size, len)?;
+
+            let data = || {
+                let len = usize::try_from(size)
+                    .map_err(|_| invalid_data("the data of the entry is too large"))?;
+                let mut buffer = vec![0; len];
+                sub_cursor.read_exact_at(data_offset, &mut buffer)?;
+                Ok::<_, io::Error>(buffer)
+            };
+
+            match block[156] {
+                b'L' => long_name = Some(trim(&data()?)),
+                b'K' => long_link_name = Some(trim(&data()?)),
+                b'x' => extended = parse_pax(&data()?)?,
+                b'g' => global.extend(parse_pax(&data()?)?),
+                kind => {
+                    let magic = &block[257..263];
+                    let mut path = trim(&block[..100]);
+
+                    // the prefix is only used by ustar (GNU uses the space
+                    // for other fields)
+                    if magic == b"ustar\0" && block[345] != 0 {
+                        path = format!("{}/{}", trim(&block[345..500]), path);
+                    }
+
+                    let mut header = TarHeader {
+                        path: long_name.take().unwrap_or(path),
+                        link_name: long_link_name
+                            .take()
+                            .unwrap_or_else(|| trim(&block[157..257])),
+                        kind: EntryType::from_byte(kind),
+                        mode: u32::try_from(parse_number(&block[100..108])?)
+                            .map_err(|_| invalid_data("invalid mode in a header"))?,
+                        uid: parse_number(&block[108..116])?,
+                        gid: parse_number(&block[116..124])?,
+                        mtime: parse_number(&block[136..148])?,
+                        uname: trim(&block[265..297]),
+                        gname: trim(&block[297..329]),
+                    };
+                    let mut size = size;
+                    for (key, value) in global.iter().chain(extended.iter()) {
+                        apply_pax(&mut header, &mut size, key, value)?;
+                    }
+                    extended.clear();
+
+                    // old archives mark directories with a trailing slash
+                    if header.kind == EntryType::Regular && header.path.ends_with('/') {
+                        header.kind = EntryType::Directory;
+                    }
+
+                    // links, directories, ... have no data
+                    let size = {
+                        if let EntryType::Regular | EntryType::Other(_) = header.kind {
+                            size
+                        } else {
+                            0
+                        }
+                    };
+                    offset = entry_end(data_offset, size, len)?;
+
+                    entries.push(TarEntry {
+                        header,
+                        header_offset,
+                        data_offset,
+                        size,
+                    });
+                    first_header = None;
+                }
+            }
+        }
+
+        Ok(Self {
+            sub_cursor,
+            entries,
+        })
+    }
+
+    /// Returns the data of `entry` as a [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the data is out of bounds.
+    pub fn data(&self, entry: &TarEntry) -> io::Result<SubCursor<T>> {
+        self.sub_cursor
+            .child(entry.data_offset, entry.size)
+            .ok_or_else(|| invalid_data("the data of the entry is out of bounds"))
+    }
+}
+
+impl<T> TarArchive<T> {
+    /// Returns all entries in the order of the archive.
+    pub fn entries(&self) -> &[TarEntry] { &self.entries }
+
+    /// Returns the last entry with the provided path.
+    pub fn by_path(&self, path: &str) -> Option<&TarEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.header.path == path)
+    }
+
+    /// Returns the number of entries.
+    pub const fn len(&self) -> usize { self.entries.len() }
+
+    /// Returns `true`, if there are no entries.
+    pub const fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// Consumes this [`TarArchive`], returning the [`SubCursor`].
+    pub fn into_inner(self) -> SubCursor<T> { self.sub_cursor }
+}
+
+/// A [`TarWriter`] appends members to a tar archive, that starts at the start
+/// of a [`SubCursor`].
+///
+/// The space for the header is reserved, then the data is streamed through a
+/// [`SubCursor`] and the header is written, when the size is known.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{SubCursor, TarHeader, TarWriter};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// let mut writer = TarWriter::new(SubCursor::new());
+///
+/// let entry = writer.append(&TarHeader::new("data.bin"), &[0_u8; 600][..])?;
+/// assert_eq!(entry.data_offset(), 512);
+///
+/// // the data is padded to a multiple of 512 bytes and followed by two
+/// // empty blocks
+/// assert_eq!(writer.finish()?.len(), 512 + 1024 + 1024);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Note
+///
+/// The end of the [`SubCursor`] is ignored, the archive grows as needed.
+/// Paths, that are too long for the ustar format, are stored in a PAX header
+/// and sizes, that are too large, are stored with the GNU base-256 encoding.
+#[derive(Debug, Clone)]
+pub struct TarWriter<T> {
+    sub_cursor: SubCursor<T>,
+    offset: u64,
+}
+
+impl<T> TarWriter<T>
+where
+    T: Write + Seek,
+{
+    /// Creates a new [`TarWriter`], that writes to the [`SubCursor`].
+    // takes ownership, like the constructor of the archive
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn new(sub_cursor: SubCursor<T>) -> Self {
+        Self {
+            sub_cursor: sub_cursor.end(usize::MAX),
+            offset: 0,
+        }
+    }
+
+    /// Appends a new member with the provided `header`, the data is copied
+    /// from `data`.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if `data` could not be read or if the
+    /// [`SubCursor`] could not be written.
+    pub fn append<R: Read>(&mut self, header: &TarHeader, mut data: R) -> io::Result<TarEntry> {
+        let header_offset = self.offset;
+
+        let (name, prefix) = split_path(&header.path);
+        if name.is_none() || header.link_name.len() > 100 {
+            let mut records = vec![];
+            pax_record(&mut records, "path", &header.path);
+            if header.link_name.len() > 100 {
+                pax_record(&mut records, "linkpath", &header.link_name);
+            }
+
+            let pax_header = TarHeader::new("././@PaxHeader").kind(EntryType::Other(b'x'));
+            self.write_header(&pax_header, records.len() as u64)?;
+
+            let len = records.len() as u64;
+            let mut window = self.window(BLOCK_SIZE, padded(len))?;
+            window.write_all(&records)?;
+
+            // fill the padding, which might contain old data
+            io::copy(&mut io::repeat(0).take(padded(len) - len), &mut window)?;
+            self.offset += BLOCK_SIZE + padded(len);
+        }
+
+        let data_offset = self.offset + BLOCK_SIZE;
+        let size = {
+            if let EntryType::Regular | EntryType::Other(_) = header.kind {
+                let mut window = self.window(BLOCK_SIZE, u64::MAX)?;
+                io::copy(&mut data, &mut window)?
+            } else {
+                0
+            }
+        };
+
+        // fill the padding, which might contain old data
+        let padding = padded(size) - size;
+        io::copy(
+            &mut io::repeat(0).take(padding),
+            &mut self.window(BLOCK_SIZE + size, padding)?,
+        )?;
+
+        // the full path is stored in the PAX header, if it does not fit
+        self.write_header_split(header, size, name, prefix)?;
+        self.offset = data_offset + padded(size);
+
+        Ok(TarEntry {
+            header: header.clone(),
+            header_offset,
+            data_offset,
+            size,
+        })
+    }
+
+    /// Writes the end of the archive (two empty blocks) and returns the
+    /// archive as a [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the [`SubCursor`] could not be written.
+    pub fn finish(self) -> io::Result<SubCursor<T>> {
+        let end = self.offset + 2 * BLOCK_SIZE;
+        self.window(0, 2 * BLOCK_SIZE)?
+            .write_all(&[0; 2 * BLOCK_LEN])?;
+
+        self.sub_cursor
+            .child(0, end)
+            .ok_or_else(|| invalid_data("the archive is too large"))
+    }
+
+    /// Returns a window of `len` bytes, that starts `offset` bytes after the
+    /// current offset.
+    fn window(&self, offset: u64, len: u64) -> io::Result<SubCursor<T>> {
+        let start = self.offset + offset;
+        let len = cmp::min(len, self.sub_cursor.len() as u64 - start);
+
+        self.sub_cursor
+            .child(start, len)
+            .ok_or_else(|| invalid_data("the archive is too large"))
+    }
+
+    fn write_header(&self, header: &TarHeader, size: u64) -> io::Result<()> {
+        let (name, prefix) = split_path(&header.path);
+        self.write_header_split(header, size, name, prefix)
+    }
+
+    fn write_header_split(
+        &self,
+        header: &TarHeader,
+        size: u64,
+        name: Option<&str>,
+        prefix: Option<&str>,
+    ) -> io::Result<()> {
+        let mut block = [0; BLOCK_LEN];
+
+        let name = name.unwrap_or_else(|| truncate(&header.path, 100));
+        block[..name.len()].copy_from_slice(name.as_bytes());
+        write_number(&mut block[100..108], u64::from(header.mode));
+        write_number(&mut block[108..116], header.uid);
+        write_number(&mut block[116..124], header.gid);
+        write_number(&mut block[124..136], size);
+        write_number(&mut block[136..148], header.mtime);
+        block[156] = header.kind.as_byte();
+
+        let link_name = truncate(&header.link_name, 100);
+        block[157..157 + link_name.len()].copy_from_slice(link_name.as_bytes());
+        block[257..265].copy_from_slice(b"ustar\x0000");
+
+        let uname = truncate(&header.uname, 32);
+        block[265..265 + uname.len()].copy_from_slice(uname.as_bytes());
+        let gname = truncate(&header.gname, 32);
+        block[297..297 + gname.len()].copy_from_slice(gname.as_bytes());
+
+        if let Some(prefix) = prefix {
+            block[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+        }
+
+        let checksum = format!("{:06o}\0 ", checksum(&block));
+        block[148..156].copy_from_slice(checksum.as_bytes());
+
+        self.window(0, BLOCK_SIZE)?.write_all(&block)
+    }
+}
+
+/// Splits a path into the name and the prefix of an ustar header, the name
+/// is `None`, if the path does not fit.
+fn split_path(path: &str) -> (Option<&str>, Option<&str>) {
+    if path.len() <= 100 {
+        return (Some(path), None);
+    }
+
+    // the prefix can be up to 155 bytes long and is separated by a slash
+    for (index, _) in path.match_indices('/') {
+        if index <= 155 && path.len() - index - 1 <= 100 && index + 1 < path.len() {
+            return (Some(&path[index + 1..]), Some(&path[..index]));
+        }
+    }
+
+    (None, None)
+}
+
+/// Returns the longest prefix of `value`, that is at most `len` bytes long.
+fn truncate(value: &str, len: usize) -> &str {
+    let mut end = cmp::min(value.len(), len);
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    &value[..end]
+}
+
+/// Returns the size rounded up to a multiple of the block size.
+const fn padded(size: u64) -> u64 { size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE }
+
+/// Returns the offset after the padded data of an entry, the data itself has
+/// to end inside of the archive.
+fn entry_end(data_offset: u64, size: u64, len: u64) -> io::Result<u64> {
+    data_offset
+        .checked_add(size)
+        .filter(|end| *end <= len)
+        .and_then(|_| size.checked_add(BLOCK_SIZE - 1))
+        .and_then(|size| data_offset.checked_add(size / BLOCK_SIZE * BLOCK_SIZE))
+        .ok_or_else(|| {
+            invalid_data(&format!(
+                "the data at {data_offset} is out of bounds (size: {size})"
+            ))
+        })
+}
+
+/// Calculates the checksum of a header, the checksum field itself is treated
+/// as spaces.
+fn checksum(block: &[u8]) -> u64 {
+    block
+        .iter()
+        .enumerate()
+        .map(|(index, byte)| {
+            if (148..156).contains(&index) {
+                u64::from(b' ')
+            } else {
+                u64::from(*byte)
+            }
+        })
+        .sum()
+}
+
+/// Parses an octal number or a GNU base-256 number.
+fn parse_number(field: &[u8]) -> io::Result<u64> {
+    if field[0] & 0x80 != 0 {
+        return Ok(field[1..]
+            .iter()
+            .fold(u64::from(field[0] & 0x7f), |result, byte| {
+                (result << 8) | u64::from(*byte)
+            }));
+    }
+
+    let text = str::from_utf8(field)
+        .map_err(|_| invalid_data("invalid number in a header"))?
+        .trim_matches(|c: char| c == '\0' || c == ' ');
+
+    if text.is_empty() {
+        return Ok(0);
+    }
+
+    u64::from_str_radix(text, 8).map_err(|_| invalid_data("invalid number in a header"))
+}
+
+/// Writes an octal number or a GNU base-256 number, if it does not fit.
+fn write_number(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+
+    if value < 1 << (3 * digits) {
+        let text = format!("{value:0digits$o}\0");
+        field.copy_from_slice(text.as_bytes());
+    } else {
+        let bytes = value.to_be_bytes();
+        let start = field.len().saturating_sub(bytes.len());
+        let skip = bytes.len().saturating_sub(field.len());
+
+        field[..start].fill(0);
+        field[start..].copy_from_slice(&bytes[skip..]);
+        field[0] |= 0x80;
+    }
+}
+
+/// Parses the records (`<length> <key>=<value>\n`) of a PAX header.
+fn parse_pax(mut data: &[u8]) -> io::Result<HashMap<String, String>> {
+    let mut result = HashMap::new();
+
+    while !data.is_empty() && data[0] != 0 {
+        let space = data
+            .iter()
+            .position(|byte| *byte == b' ')
+            .ok_or_else(|| invalid_data("invalid pax record"))?;
+
+        let len = str::from_utf8(&data[..space])
+            .ok()
+            .and_then(|len| len.parse::<usize>().ok())
+            .filter(|len| *len > space && *len <= data.len())
+            .ok_or_else(|| invalid_data("invalid pax record"))?;
+
+        let record = String::from_utf8_lossy(&data[space + 1..len - 1]);
+        if let Some(index) = record.find('=') {
+            result.insert(record[..index].to_string(), record[index + 1..].to_string());
+        }
+
+        data = &data[len..];
+    }
+
+    Ok(result)
+}
+
+/// Overrides a field of the header (or the size) with a PAX record.
+fn apply_pax(header: &mut TarHeader, size: &mut u64, key: &str, value: &str) -> io::Result<()> {
+    let number = || {
+        value
+            .split('.')
+            .next()
+            .and_then(|value| value.parse::<u64>().ok())
+            .ok_or_else(|| invalid_data("invalid number in a pax header"))
+    };
+
+    match key {
+        "path" => value.clone_into(&mut header.path),
+        "linkpath" => value.clone_into(&mut header.link_name),
+        "uname" => value.clone_into(&mut header.uname),
+        "gname" => value.clone_into(&mut header.gname),
+        "uid" => header.uid = number()?,
+        "gid" => header.gid = number()?,
+        "mtime" => header.mtime = number()?,
+        "size" => *size = number()?,
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Appends a PAX record, the length includes the length itself.
+fn pax_record(buffer: &mut Vec<u8>, key: &str, value: &str) {
+    let len = key.len() + value.len() + 3;
+
+    let mut total = len + len.to_string().len();
+    if total.to_string().len() != len.to_string().len() {
+        total += 1;
+    }
+
+    buffer.extend_from_slice(format!("{total} {key}={value}\n").as_bytes());
+}
+
+/// Converts a nul-terminated field to a string.
+fn trim(field: &[u8]) -> String {
+    let end = field
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(field.len());
+
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+fn invalid_data(message: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, message) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_numbers() -> io::Result<()> {
+        let mut field = [0; 12];
+        write_number(&mut field, 0o755);
+        assert_eq!(&field, b"00000000755\0");
+        assert_eq!(parse_number(&field)?, 0o755);
+
+        // 8 GiB do not fit in 11 octal digits
+        write_number(&mut field, 1 << 33);
+        assert_eq!(field[0], 0x80);
+        assert_eq!(parse_number(&field)?, 1 << 33);
+
+        assert_eq!(parse_number(b"  644 \0")?, 0o644);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pax_record() -> io::Result<()> {
+        let mut buffer = vec![];
+        pax_record(&mut buffer, "path", "a");
+        pax_record(&mut buffer, "path", &"a".repeat(91));
+        assert_eq!(&buffer[..9], b"9 path=a\n");
+        assert_eq!(&buffer[9..13], b"101 ");
+        assert_eq!(buffer.len(), 9 + 101);
+
+        let records = parse_pax(&buffer)?;
+        assert_eq!(records["path"], "a".repeat(91));
+        Ok(())
+    }
+
+    #[test]
+    fn test_gnu_long_name() -> io::Result<()> {
+        let name = "n".repeat(200);
+        let mut writer = TarWriter::new(SubCursor::new());
+
+        writer.write_header(
+            &TarHeader::new("././@LongLink").kind(EntryType::Other(b'L')),
+            name.len() as u64 + 1,
+        )?;
+        writer
+            .window(BLOCK_SIZE, BLOCK_SIZE)?
+            .write_all(format!("{name}\0").as_bytes())?;
+        writer.offset += 2 * BLOCK_SIZE;
+        writer.append(&TarHeader::new("truncated"), &b"data"[..])?;
+
+        let archive = TarArchive::new(writer.finish()?)?;
+        assert_eq!(archive.len(), 1);
+        assert_eq!(archive.entries()[0].header().get_path(), name);
+        assert_eq!(archive.entries()[0].header_offset(), 0);
+        assert_eq!(archive.entries()[0].data_offset(), 3 * BLOCK_SIZE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_size() -> io::Result<()> {
+        for size in &[u64::MAX, u64::MAX - 600, 4096] {
+            let mut writer = TarWriter::new(SubCursor::new());
+            writer.write_header(&TarHeader::new("huge"), *size)?;
+            writer.offset += BLOCK_SIZE;
+
+            let result = TarArchive::new(writer.finish()?);
+            assert!(matches!(result, Err(error) if error.kind() == io::ErrorKind::InvalidData));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek, Write};
+
+use crate::endian::Endian;
+use crate::SubCursor;
+
+const STRIP_OFFSETS: u16 = 273;
+const STRIP_BYTE_COUNTS: u16 = 279;
+const TILE_OFFSETS: u16 = 324;
+const TILE_BYTE_COUNTS: u16 = 325;
+
+/// An entry (a tag) of an [`Ifd`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IfdEntry {
+    tag: u16,
+    field_type: u16,
+    count: u32,
+    value_offset: u64,
+}
+
+impl IfdEntry {
+    /// Returns the tag of the entry (like `256` for the width of the image).
+    pub const fn tag(&self) -> u16 { self.tag }
+
+    /// Returns the type of the values (like `3` for `SHORT`).
+    pub const fn field_type(&self) -> u16 { self.field_type }
+
+    /// Returns the number of values.
+    pub const fn count(&self) -> u32 { self.count }
+
+    /// Returns the size of a single value or `None`, if the type is
+    /// unknown.
+    pub const fn type_size(&self) -> Option<u64> {
+        match self.field_type {
+            1 | 2 | 6 | 7 => Some(1),
+            3 | 8 => Some(2),
+            4 | 9 | 11 | 13 => Some(4),
+            5 | 10 | 12 | 16 | 17 | 18 => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Returns the size of all values or `None`, if the type is unknown.
+    pub fn value_size(&self) -> Option<u64> {
+        self.type_size().map(|size| size * u64::from(self.count))
+    }
+
+    /// Returns `true`, if the values are stored in the entry itself,
+    /// because they fit in 4 bytes.
+    pub fn is_inline(&self) -> bool { self.value_size().is_none_or(|size| size <= 4) }
+
+    /// Returns the offset of the values.
+    pub const fn value_offset(&self) -> u64 { self.value_offset }
+}
+
+/// An image file directory, which is a list of tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ifd {
+    offset: u64,
+    entries: Vec<IfdEntry>,
+}
+
+impl Ifd {
+    /// Returns the offset of the [`Ifd`].
+    pub const fn offset(&self) -> u64 { self.offset }
+
+    /// Returns all entries in the order of the file.
+    pub fn entries(&self) -> &[IfdEntry] { &self.entries }
+
+    /// Returns the entry with the provided tag.
+    pub fn entry(&self, tag: u16) -> Option<&IfdEntry> {
+        self.entries.iter().find(|entry| entry.tag == tag)
+    }
+}
+
+/// A [`TiffFile`] walks the chain of image file directories of a TIFF file
+/// or of EXIF data, that is located in a [`SubCursor`].
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{Endian, SubCursor, TiffFile};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// let mut data = b"II*\0\x08\0\0\0".to_vec();
+/// // one entry: ImageWidth (SHORT) = 640
+/// data.extend_from_slice(b"\x01\0\x00\x01\x03\0\x01\0\0\0\x80\x02\0\0");
+/// data.extend_from_slice(b"\0\0\0\0");
+///
+/// let tiff = TiffFile::new(SubCursor::from(data))?;
+/// assert_eq!(tiff.endian(), Endian::Little);
+///
+/// let width = tiff.ifds()[0].entry(256).unwrap();
+/// assert_eq!(tiff.integers(width)?, vec![640]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Note
+///
+/// Both byte orders are supported. The offsets of EXIF data are relative to
+/// the TIFF header, so the [`SubCursor`] has to start at the header (after
+/// `Exif\0\0` in a JPEG file).
+#[derive(Debug, Clone)]
+pub struct TiffFile<T> {
+    sub_cursor: SubCursor<T>,
+    endian: Endian,
+    ifds: Vec<Ifd>,
+}
+
+impl<T> TiffFile<T>
+where
+    T: Read + Seek,
+{
+    /// Reads the header and the chain of image file directories of the TIFF
+    /// file in the [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the header or an [`Ifd`] is invalid, if
+    /// the chain contains a loop or if the [`SubCursor`] could not be read.
+    pub fn new(sub_cursor: SubCursor<T>) -> io::Result<Self> {
+        let mut header = [0; 8];
+        sub_cursor.read_exact_at(0, &mut header)?;
+
+        let endian = match &header[..4] {
+            b"II*\0" => Endian::Little,
+            b"MM\0*" => Endian::Big,
+            _ => return Err(invalid_data("invalid tiff header")),
+        };
+
+        let mut tiff = Self {
+            sub_cursor,
+            endian,
+            ifds: vec![],
+        };
+        tiff.ifds = tiff.read_chain(u64::from(endian.u32(&header[4..])))?;
+
+        Ok(tiff)
+    }
+
+    /// Reads the chains of image file directories, whose offsets are the
+    /// values of `entry` (like the EXIF tag `34665` or the `SubIFDs` tag
+    /// `330`).
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if an [`Ifd`] is invalid, if a chain
+    /// contains a loop or if the [`SubCursor`] could not be read.
+    pub fn sub_ifds(&self, entry: &IfdEntry) -> io::Result<Vec<Ifd>> {
+        let mut result = vec![];
+
+        for offset in self.integers(entry)? {
+            result.extend(self.read_chain(offset)?);
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the values of `entry` as a [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the type of the entry is unknown or if
+    /// the values are out of bounds.
+    pub fn value(&self, entry: &IfdEntry) -> io::Result<SubCursor<T>> {
+        let size = entry
+            .value_size()
+            .ok_or_else(|| invalid_data("unknown type of a tag"))?;
+
+        self.sub_cursor
+            .child(entry.value_offset, size)
+            .ok_or_else(|| invalid_data("the value of a tag is out of bounds"))
+    }
+
+    /// Reads the values of `entry`, which have an unsigned integer type
+    /// (`BYTE`, `SHORT`, `LONG`, `IFD` or `LONG8`).
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the values are not unsigned integers or
+    /// if they could not be read.
+    pub fn integers(&self, entry: &IfdEntry) -> io::Result<Vec<u64>> {
+        let size = integer_size(entry)?;
+
+        // the value is checked against the bounds before anything is allocated
+        let value = self.value(entry)?;
+        let mut bytes = vec![0; value.len()];
+        value.read_exact_at(0, &mut bytes)?;
+
+        Ok(bytes
+            .chunks_exact(size)
+            .map(|value| {
+                match size {
+                    1 => u64::from(value[0]),
+                    2 => u64::from(self.endian.u16(value)),
+                    4 => u64::from(self.endian.u32(value)),
+                    _ => self.endian.u64(value),
+                }
+            })
+            .collect())
+    }
+
+    /// Returns the strips or the tiles of the image, that is described by
+    /// `ifd`, as [`SubCursor`]s.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the offsets or the byte counts are
+    /// missing, if their number is different or if a strip is out of bounds.
+    pub fn strips(&self, ifd: &Ifd) -> io::Result<Vec<SubCursor<T>>> {
+        let (offsets, byte_counts) = {
+            match (ifd.entry(STRIP_OFFSETS), ifd.entry(STRIP_BYTE_COUNTS)) {
+                (Some(offsets), Some(byte_counts)) => (offsets, byte_counts),
+                _ => {
+                    ifd.entry(TILE_OFFSETS)
+                        .and_then(|offsets| Some((offsets, ifd.entry(TILE_BYTE_COUNTS)?)))
+                        .ok_or_else(|| invalid_data("missing strip offsets or byte counts"))?
+                }
+            }
+        };
+
+        let offsets = self.integers(offsets)?;
+        let byte_counts = self.integers(byte_counts)?;
+
+        if offsets.len() != byte_counts.len() {
+            return Err(invalid_data(
+                "the number of offsets and byte counts is different",
+            ));
+        }
+
+        offsets
+            .iter()
+            .zip(&byte_counts)
+            .map(|(offset, byte_count)| {
+                self.sub_cursor
+                    .child(*offset, *byte_count)
+                    .ok_or_else(|| invalid_data("a strip is out of bounds"))
+            })
+            .collect()
+    }
+
+    /// Reads the chain of image file directories, that starts at `offset`.
+    fn read_chain(&self, mut offset: u64) -> io::Result<Vec<Ifd>> {
+        let mut visited = HashSet::new();
+        let mut result = vec![];
+
+        // the chain ends with an offset of 0
+        while offset != 0 {
+            if !visited.insert(offset) {
+                return Err(invalid_data(
+                    "the chain of image file directories contains a loop",
+                ));
+            }
+
+            let mut count = [0; 2];
+            self.sub_cursor.read_exact_at(offset, &mut count)?;
+            let count = usize::from(self.endian.u16(&count));
+
+            let mut entries = vec![0; count * 12 + 4];
+            self.sub_cursor.read_exact_at(offset + 2, &mut entries)?;
+
+            let entries_offset = offset + 2;
+            let ifd = Ifd {
+                offset,
+                entries: entries
+                    .chunks_exact(12)
+                    .enumerate()
+                    .map(|(index, bytes)| {
+                        let mut entry = IfdEntry {
+                            tag: self.endian.u16(bytes),
+                            field_type: self.endian.u16(&bytes[2..]),
+                            count: self.endian.u32(&bytes[4..]),
+                            value_offset: entries_offset + index as u64 * 12 + 8,
+                        };
+
+                        if !entry.is_inline() {
+                            entry.value_offset = u64::from(self.endian.u32(&bytes[8..]));
+                        }
+
+                        entry
+                    })
+                    .collect(),
+            };
+
+            offset = u64::from(self.endian.u32(&entries[count * 12..]));
+            result.push(ifd);
+        }
+
+        Ok(result)
+    }
+}
+
+impl<T> TiffFile<T>
+where
+    T: Read + Write + Seek,
+{
+    /// Overwrites the values of `entry` in place with `bytes`, which have to
+    /// be in the byte order of the file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::{SubCursor, TiffFile};
+    /// # use std::io;
+    /// # fn main() -> io::Result<()> {
+    /// use std::io::Cursor;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mut data = b"MM\0*\0\0\0\x08".to_vec();
+    /// // one entry: Orientation (SHORT) = 1
+    /// data.extend_from_slice(b"\0\x01\x01\x12\0\x03\0\0\0\x01\0\x01\0\0");
+    /// data.extend_from_slice(b"\0\0\0\0");
+    ///
+    /// let cursor = Arc::new(Mutex::new(Cursor::new(data)));
+    /// let tiff = TiffFile::new(SubCursor::from(cursor.clone()).end(26))?;
+    ///
+    /// let orientation = *tiff.ifds()[0].entry(274).unwrap();
+    /// tiff.set_value(&orientation, &6_u16.to_be_bytes())?;
+    /// assert_eq!(tiff.integers(&orientation)?, vec![6]);
+    ///
+    /// // the size of a value can not be changed
+    /// assert!(tiff.set_value(&orientation, &[0; 4]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if `bytes` does not have the same size as
+    /// the values or if the [`SubCursor`] could not be written.
+    pub fn set_value(&self, entry: &IfdEntry, bytes: &[u8]) -> io::Result<()> {
+        let mut value = self.value(entry)?;
+
+        if bytes.len() != value.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "the value is {} bytes long, but the tag is {} bytes long",
+                    bytes.len(),
+                    value.len()
+                ),
+            ));
+        }
+
+        value.write_all(bytes)
+    }
+
+    /// Overwrites the values of `entry`, which have an unsigned integer
+    /// type, in place with `values`.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the number of values is different, if a
+    /// value does not fit in the type or if the [`SubCursor`] could not be
+    /// written.
+    pub fn set_integers(&self, entry: &IfdEntry, values: &[u64]) -> io::Result<()> {
+        let size = integer_size(entry)?;
+        let mut bytes = vec![0; values.len() * size];
+
+        for (value, bytes) in values.iter().zip(bytes.chunks_exact_mut(size)) {
+            let too_large = |_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{value} does not fit in the type of the tag"),
+                )
+            };
+
+            match size {
+                1 => bytes[0] = u8::try_from(*value).map_err(too_large)?,
+                2 => {
+                    let value = u16::try_from(*value).map_err(too_large)?;
+                    self.endian.write_u16(bytes, value);
+                }
+                4 => {
+                    let value = u32::try_from(*value).map_err(too_large)?;
+                    self.endian.write_u32(bytes, value);
+                }
+                _ => self.endian.write_u64(bytes, *value),
+            }
+        }
+
+        self.set_value(entry, &bytes)
+    }
+}
+
+impl<T> TiffFile<T> {
+    /// Returns the byte order of the file.
+    pub const fn endian(&self) -> Endian { self.endian }
+
+    /// Returns the chain of image file directories, that starts in the
+    /// header.
+    pub fn ifds(&self) -> &[Ifd] { &self.ifds }
+
+    /// Consumes this [`TiffFile`], returning the [`SubCursor`].
+    pub fn into_inner(self) -> SubCursor<T> { self.sub_cursor }
+}
+
+/// Returns the size of the values of `entry`, if they are unsigned integers.
+fn integer_size(entry: &IfdEntry) -> io::Result<usize> {
+    match entry.field_type {
+        1 => Ok(1),
+        3 => Ok(2),
+        4 | 13 => Ok(4),
+        16 | 18 => Ok(8),
+        _ => Err(invalid_data("the tag does not contain unsigned integers")),
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, message) }
@@ -0,0 +1,110 @@
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use crate::{Overlay, SubCursor};
+
+/// A [`Transaction`] buffers all writes to a [`SubCursor`], until it is
+/// [`commit`]ted.
+///
+/// If the [`Transaction`] is dropped without calling [`commit`], the
+/// underlying stream will not be changed.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::SubCursor;
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::{Cursor, Write};
+/// use std::sync::{Arc, Mutex};
+///
+/// let cursor = Arc::new(Mutex::new(Cursor::new(vec![0; 8])));
+/// let sub_cursor = SubCursor::from(cursor.clone()).end(8);
+///
+/// let mut transaction = sub_cursor.transaction();
+/// transaction.write_all(&[1, 2, 3, 4])?;
+/// // something failed, so the changes are thrown away:
+/// transaction.rollback();
+/// assert_eq!(cursor.lock().unwrap().get_ref(), &[0; 8]);
+///
+/// let mut transaction = sub_cursor.transaction();
+/// transaction.write_all(&[1, 2, 3, 4])?;
+/// transaction.commit()?;
+/// assert_eq!(cursor.lock().unwrap().get_ref(), &[1, 2, 3, 4, 0, 0, 0, 0]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`commit`]: #method.commit
+#[must_use = "a transaction does nothing, unless it is committed"]
+#[derive(Default, Debug, Clone)]
+pub struct Transaction<T> {
+    overlay: Overlay<T>,
+}
+
+impl<T> SubCursor<T> {
+    /// Starts a new [`Transaction`] on this [`SubCursor`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sub_cursor::SubCursor;
+    /// let transaction = SubCursor::from(vec![1, 2, 3]).transaction();
+    /// # transaction.rollback();
+    /// ```
+    ///
+    /// [`Transaction`]: crate::Transaction
+    pub fn transaction(&self) -> Transaction<T> {
+        Transaction {
+            overlay: self.overlay(),
+        }
+    }
+}
+
+impl<T> Transaction<T> {
+    /// Ends the [`Transaction`], without changing the underlying stream.
+    ///
+    /// This is the same as dropping the [`Transaction`].
+    pub fn rollback(self) {}
+}
+
+impl<T> Transaction<T>
+where
+    T: Write + Seek,
+{
+    /// Applies all writes of this [`Transaction`] to the underlying stream.
+    ///
+    /// The underlying stream stays locked, while the changes are applied, so
+    /// other [`SubCursor`]s sharing the stream will never observe a partially
+    /// committed [`Transaction`].
+    ///
+    /// # Errors
+    ///
+    /// If writing to the underlying stream fails, the stream might contain
+    /// some of the changes.
+    ///
+    /// [`SubCursor`]: crate::SubCursor
+    pub fn commit(mut self) -> io::Result<()> { self.overlay.commit() }
+}
+
+impl<T> Seek for Transaction<T>
+where
+    T: Seek,
+{
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> { self.overlay.seek(style) }
+}
+
+impl<T> Read for Transaction<T>
+where
+    T: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.overlay.read(buf) }
+}
+
+impl<T> Write for Transaction<T>
+where
+    T: Seek,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.overlay.write(buf) }
+
+    fn flush(&mut self) -> io::Result<()> { self.overlay.flush() }
+}
@@ -0,0 +1,84 @@
+use std::io::{self, Read, SeekFrom, Write};
+
+/// Calculates the new position of an unbounded stream with the length `len`,
+/// which is currently at `position`.
+///
+/// Unlike a [`SubCursor`], the position might be after the end of the stream.
+///
+/// [`SubCursor`]: crate::SubCursor
+pub fn seek_position(position: u64, len: u64, style: SeekFrom) -> io::Result<u64> {
+    let (base, offset) = match style {
+        SeekFrom::Start(offset) => return Ok(offset),
+        SeekFrom::End(offset) => (len, offset),
+        SeekFrom::Current(offset) => (position, offset),
+    };
+
+    let new_position = {
+        if offset >= 0 {
+            base.checked_add(offset.unsigned_abs())
+        } else {
+            base.checked_sub(offset.unsigned_abs())
+        }
+    };
+
+    new_position.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid seek to a negative or overflowing position",
+        )
+    })
+}
+
+/// Writes `value` as an unsigned LEB128 varint.
+pub fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Reads an unsigned LEB128 varint.
+pub fn read_varint<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut result = 0;
+
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0; 1];
+        reader.read_exact(&mut byte)?;
+
+        result |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "varint is too long",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_varint() -> io::Result<()> {
+        for value in &[0, 1, 127, 128, 300, u64::MAX] {
+            let mut buffer = vec![];
+            write_varint(&mut buffer, *value)?;
+            assert_eq!(read_varint(&mut buffer.as_slice())?, *value);
+        }
+
+        let mut buffer = vec![];
+        write_varint(&mut buffer, 300)?;
+        assert_eq!(buffer, vec![0xac, 0x02]);
+        Ok(())
+    }
+}
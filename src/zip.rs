@@ -0,0 +1,429 @@
+use std::cmp;
+use std::convert::TryFrom;
+use std::io::{self, BufReader, Read, Seek};
+
+use flate2::read::DeflateDecoder;
+
+use crate::endian::Endian::Little;
+use crate::SubCursor;
+
+const LOCAL_HEADER: &[u8; 4] = b"PK\x03\x04";
+const CENTRAL_HEADER: &[u8; 4] = b"PK\x01\x02";
+const END_OF_CENTRAL_DIRECTORY: &[u8; 4] = b"PK\x05\x06";
+const ZIP64_END_OF_CENTRAL_DIRECTORY: &[u8; 4] = b"PK\x06\x06";
+const ZIP64_LOCATOR: &[u8; 4] = b"PK\x06\x07";
+
+const LOCAL_HEADER_LEN: usize = 30;
+const CENTRAL_HEADER_LEN: usize = 46;
+const END_OF_CENTRAL_DIRECTORY_LEN: usize = 22;
+const ZIP64_END_OF_CENTRAL_DIRECTORY_LEN: usize = 56;
+const ZIP64_LOCATOR_LEN: usize = 20;
+const ZIP64_EXTRA_FIELD: u16 = 0x0001;
+
+/// A [`ZipArchive`] lists the entries of a zip file, that is located in a
+/// [`SubCursor`].
+///
+/// Only the central directory is read, the data of an entry is returned as a
+/// [`SubCursor`], that shares the stream with the archive.
+///
+/// # Example
+///
+/// ```
+/// # use sub_cursor::{SubCursor, ZipArchive};
+/// # use std::io;
+/// # fn main() -> io::Result<()> {
+/// use std::io::Read;
+///
+/// # let data = vec![
+/// #     0x50, 0x4b, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x21, 0x00, 0x82, 0x89,
+/// #     0xd1, 0xf7, 0x05, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x68, 0x65,
+/// #     0x6c, 0x6c, 0x6f, 0x2e, 0x74, 0x78, 0x74, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x50, 0x4b, 0x01, 0x02,
+/// #     0x14, 0x03, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x21, 0x00, 0x82, 0x89, 0xd1, 0xf7,
+/// #     0x05, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+/// #     0x00, 0x00, 0x00, 0x00, 0x80, 0x01, 0x00, 0x00, 0x00, 0x00, 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x2e,
+/// #     0x74, 0x78, 0x74, 0x50, 0x4b, 0x05, 0x06, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x37,
+/// #     0x00, 0x00, 0x00, 0x2c, 0x00, 0x00, 0x00, 0x00, 0x00,
+/// # ];
+/// // a zip file with a single file "hello.txt"
+/// let archive = ZipArchive::new(SubCursor::from(data))?;
+///
+/// let entry = archive.by_name("hello.txt").unwrap();
+/// assert_eq!(entry.size(), 5);
+///
+/// let mut result = String::new();
+/// archive.reader(entry)?.read_to_string(&mut result)?;
+/// assert_eq!(result, "Hello".to_string());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ZipArchive<T> {
+    sub_cursor: SubCursor<T>,
+    entries: Vec<ZipEntry>,
+    comment: Vec<u8>,
+}
+
+/// The method, that has been used to compress a [`ZipEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompressionMethod {
+    /// The data is not compressed.
+    Stored,
+    /// The data is compressed with deflate.
+    Deflated,
+    /// Any other (unsupported) method.
+    Other(u16),
+}
+
+impl From<u16> for CompressionMethod {
+    fn from(value: u16) -> Self {
+        match value {
+            0 => Self::Stored,
+            8 => Self::Deflated,
+            value => Self::Other(value),
+        }
+    }
+}
+
+/// An entry of a [`ZipArchive`], as it is described in the central
+/// directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZipEntry {
+    name: String,
+    comment: Vec<u8>,
+    flags: u16,
+    method: CompressionMethod,
+    dos_time: u16,
+    dos_date: u16,
+    crc32: u32,
+    compressed_size: u64,
+    size: u64,
+    // the offset of the local header in the archive
+    header_offset: u64,
+}
+
+impl ZipEntry {
+    /// Returns the name (path) of the entry.
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Returns the comment of the entry.
+    pub fn comment(&self) -> &[u8] { &self.comment }
+
+    /// Returns the general purpose flags of the entry.
+    pub const fn flags(&self) -> u16 { self.flags }
+
+    /// Returns the method, that has been used to compress the entry.
+    pub const fn compression_method(&self) -> CompressionMethod { self.method }
+
+    /// Returns the last modification date and time in the MS-DOS format.
+    pub const fn dos_date_time(&self) -> (u16, u16) { (self.dos_date, self.dos_time) }
+
+    /// Returns the CRC-32 of the uncompressed data.
+    pub const fn crc32(&self) -> u32 { self.crc32 }
+
+    /// Returns the size of the compressed data.
+    pub const fn compressed_size(&self) -> u64 { self.compressed_size }
+
+    /// Returns the size of the uncompressed data.
+    pub const fn size(&self) -> u64 { self.size }
+
+    /// Returns `true`, if the entry is a directory.
+    pub fn is_dir(&self) -> bool { self.name.ends_with('/') }
+
+    /// Returns `true`, if the entry is encrypted.
+    pub const fn is_encrypted(&self) -> bool { self.flags & 1 != 0 }
+}
+
+impl<T> ZipArchive<T>
+where
+    T: Read + Seek,
+{
+    /// Reads the central directory of the zip file in the [`SubCursor`].
+    ///
+    /// # Note
+    ///
+    /// Zip64 archives are supported, archives, that are split into many
+    /// disks, are not supported (see [`MultiVolume`] for split archives).
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the [`SubCursor`] does not contain a
+    /// valid zip file or if it could not be read.
+    ///
+    /// [`MultiVolume`]: crate::MultiVolume
+    pub fn new(sub_cursor: SubCursor<T>) -> io::Result<Self> {
+        let (position, end) = find_end_of_central_directory(&sub_cursor)?;
+
+        let disk = Little.u16(&end[4..]);
+        let central_directory_disk = Little.u16(&end[6..]);
+        let mut disk_entries = u64::from(Little.u16(&end[8..]));
+        let mut entries = u64::from(Little.u16(&end[10..]));
+        let mut size = u64::from(Little.u32(&end[12..]));
+        let mut offset = u64::from(Little.u32(&end[16..]));
+
+        let comment = sub_cursor.read_range(
+            position + END_OF_CENTRAL_DIRECTORY_LEN,
+            usize::from(Little.u16(&end[20..])),
+        )?;
+
+        let mut zip64 = false;
+        if let Some(locator) = position.checked_sub(ZIP64_LOCATOR_LEN) {
+            let mut buffer = [0; ZIP64_LOCATOR_LEN];
+            sub_cursor.read_exact_at(locator as u64, &mut buffer)?;
+
+            if &buffer[..4] == ZIP64_LOCATOR {
+                let mut end = [0; ZIP64_END_OF_CENTRAL_DIRECTORY_LEN];
+                sub_cursor.read_exact_at(Little.u64(&buffer[8..]), &mut end)?;
+
+                if &end[..4] != ZIP64_END_OF_CENTRAL_DIRECTORY {
+                    return Err(invalid_data("invalid zip64 end of central directory"));
+                }
+
+                disk_entries = Little.u64(&end[24..]);
+                entries = Little.u64(&end[32..]);
+                size = Little.u64(&end[40..]);
+                offset = Little.u64(&end[48..]);
+                zip64 = true;
+            }
+        }
+
+        // archives, that have been prepended with other data (like
+        // self-extracting archives) have a shifted central directory
+        let base = {
+            if zip64 {
+                0
+            } else {
+                (position as u64)
+                    .checked_sub(size + offset)
+                    .ok_or_else(|| invalid_data("the central directory is out of bounds"))?
+            }
+        };
+
+        if disk != central_directory_disk || disk_entries != entries {
+            return Err(io::Error::other(
+                "zip archives with many disks are not supported",
+            ));
+        }
+
+        let central_directory = sub_cursor
+            .child(base + offset, size)
+            .ok_or_else(|| invalid_data("the central directory is out of bounds"))?;
+
+        let mut reader = BufReader::new(central_directory);
+        let mut result =
+            Vec::with_capacity(usize::try_from(cmp::min(entries, 0xFFFF)).unwrap_or(0));
+
+        for _ in 0..entries {
+            let mut entry = read_central_header(&mut reader)?;
+            entry.header_offset = entry
+                .header_offset
+                .checked_add(base)
+                .ok_or_else(|| invalid_data("the local header is out of bounds"))?;
+            result.push(entry);
+        }
+
+        Ok(Self {
+            sub_cursor,
+            entries: result,
+            comment,
+        })
+    }
+
+    /// Returns the compressed data of `entry` as a [`SubCursor`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the local header of the entry is invalid
+    /// or if it could not be read.
+    pub fn data(&self, entry: &ZipEntry) -> io::Result<SubCursor<T>> {
+        let mut header = [0; LOCAL_HEADER_LEN];
+        self.sub_cursor
+            .read_exact_at(entry.header_offset, &mut header)?;
+
+        if &header[..4] != LOCAL_HEADER {
+            return Err(invalid_data("invalid local file header"));
+        }
+
+        let offset = entry
+            .header_offset
+            .checked_add(
+                LOCAL_HEADER_LEN as u64
+                    + u64::from(Little.u16(&header[26..]))
+                    + u64::from(Little.u16(&header[28..])),
+            )
+            .ok_or_else(|| invalid_data("the data of the entry is out of bounds"))?;
+
+        self.sub_cursor
+            .child(offset, entry.compressed_size)
+            .ok_or_else(|| invalid_data("the data of the entry is out of bounds"))
+    }
+
+    /// Returns a reader, that decompresses the data of `entry`.
+    ///
+    /// # Errors
+    ///
+    /// This function will error, if the entry is encrypted, if the
+    /// compression method is not supported or for the same reasons as
+    /// [`data`].
+    ///
+    /// [`data`]: #method.data
+    pub fn reader(&self, entry: &ZipEntry) -> io::Result<ZipReader<T>> {
+        if entry.is_encrypted() {
+            return Err(io::Error::other("encrypted entries are not supported"));
+        }
+
+        let data = self.data(entry)?;
+        let inner = match entry.method {
+            CompressionMethod::Stored => Inner::Stored(data),
+            CompressionMethod::Deflated => Inner::Deflated(DeflateDecoder::new(data)),
+            CompressionMethod::Other(method) => {
+                return Err(io::Error::other(format!(
+                    "the compression method {method} is not supported"
+                )));
+            }
+        };
+
+        Ok(ZipReader { inner })
+    }
+}
+
+impl<T> ZipArchive<T> {
+    /// Returns all entries in the order of the central directory.
+    pub fn entries(&self) -> &[ZipEntry] { &self.entries }
+
+    /// Returns the entry with the provided name.
+    pub fn by_name(&self, name: &str) -> Option<&ZipEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Returns the number of entries.
+    pub const fn len(&self) -> usize { self.entries.len() }
+
+    /// Returns `true`, if there are no entries.
+    pub const fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// Returns the comment of the archive.
+    pub fn comment(&self) -> &[u8] { &self.comment }
+
+    /// Consumes this [`ZipArchive`], returning the [`SubCursor`].
+    pub fn into_inner(self) -> SubCursor<T> { self.sub_cursor }
+}
+
+/// A reader, that decompresses the data of a [`ZipEntry`], created by
+/// [`ZipArchive::reader`].
+///
+/// [`ZipArchive::reader`]: crate::ZipArchive::reader
+#[derive(Debug)]
+pub struct ZipReader<T> {
+    inner: Inner<T>,
+}
+
+#[derive(Debug)]
+enum Inner<T> {
+    Stored(SubCursor<T>),
+    Deflated(DeflateDecoder<SubCursor<T>>),
+}
+
+impl<T> Read for ZipReader<T>
+where
+    T: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            Inner::Stored(reader) => reader.read(buf),
+            Inner::Deflated(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// Returns the position and the fixed part of the end of central directory
+/// record.
+fn find_end_of_central_directory<T>(
+    sub_cursor: &SubCursor<T>,
+) -> io::Result<(usize, [u8; END_OF_CENTRAL_DIRECTORY_LEN])>
+where
+    T: Read + Seek,
+{
+    let len = sub_cursor.len();
+    // the record is followed by a comment with up to 0xFFFF bytes
+    let start = len.saturating_sub(END_OF_CENTRAL_DIRECTORY_LEN + 0xFFFF);
+    let tail = sub_cursor
+        .child(start as u64, (len - start) as u64)
+        .unwrap_or_else(|| sub_cursor.sub_cursor());
+
+    let mut end = tail.len();
+    while let Some(found) = tail.rfind_until(end, END_OF_CENTRAL_DIRECTORY, 1024)? {
+        let position = start + found;
+        let mut buffer = [0; END_OF_CENTRAL_DIRECTORY_LEN];
+
+        // the signature might be part of a comment
+        if sub_cursor
+            .read_exact_at(position as u64, &mut buffer)
+            .is_ok()
+            && position + END_OF_CENTRAL_DIRECTORY_LEN + Little.u16(&buffer[20..]) as usize <= len
+        {
+            return Ok((position, buffer));
+        }
+
+        end = found + END_OF_CENTRAL_DIRECTORY.len() - 1;
+    }
+
+    Err(invalid_data("missing end of central directory record"))
+}
+
+fn read_central_header<R: Read>(reader: &mut R) -> io::Result<ZipEntry> {
+    let mut header = [0; CENTRAL_HEADER_LEN];
+    reader.read_exact(&mut header)?;
+
+    if &header[..4] != CENTRAL_HEADER {
+        return Err(invalid_data("invalid central directory file header"));
+    }
+
+    let mut name = vec![0; Little.u16(&header[28..]) as usize];
+    let mut extra = vec![0; Little.u16(&header[30..]) as usize];
+    let mut comment = vec![0; Little.u16(&header[32..]) as usize];
+    reader.read_exact(&mut name)?;
+    reader.read_exact(&mut extra)?;
+    reader.read_exact(&mut comment)?;
+
+    let mut size = u64::from(Little.u32(&header[24..]));
+    let mut compressed_size = u64::from(Little.u32(&header[20..]));
+    let mut header_offset = u64::from(Little.u32(&header[42..]));
+
+    // the zip64 extra field only contains the values, that did not fit
+    let mut fields = extra.as_slice();
+    while fields.len() >= 4 {
+        let id = Little.u16(fields);
+        let len = cmp::min(Little.u16(&fields[2..]) as usize, fields.len() - 4);
+        let mut data = &fields[4..4 + len];
+
+        if id == ZIP64_EXTRA_FIELD {
+            for value in &mut [&mut size, &mut compressed_size, &mut header_offset] {
+                if **value == 0xFFFF_FFFF {
+                    if data.len() < 8 {
+                        return Err(invalid_data("truncated zip64 extra field"));
+                    }
+
+                    **value = Little.u64(data);
+                    data = &data[8..];
+                }
+            }
+        }
+
+        fields = &fields[4 + len..];
+    }
+
+    Ok(ZipEntry {
+        name: String::from_utf8_lossy(&name).into_owned(),
+        comment,
+        flags: Little.u16(&header[8..]),
+        method: CompressionMethod::from(Little.u16(&header[10..])),
+        dos_time: Little.u16(&header[12..]),
+        dos_date: Little.u16(&header[14..]),
+        crc32: Little.u32(&header[16..]),
+        compressed_size,
+        size,
+        header_offset,
+    })
+}
+
+fn invalid_data(message: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, message) }
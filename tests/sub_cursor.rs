@@ -2,7 +2,27 @@ use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 use std::sync::{Arc, Mutex};
 
 use pretty_assertions::assert_eq;
-use sub_cursor::SubCursor;
+use sub_cursor::{
+    ChainCursor, CoverageTracker, Decode, DirtyTracker, Encode, Event, LineIndex, MultiVolume,
+    PositionalFile, RecordCursor, Recorder, Registry, Replay, SubCursor,
+};
+
+/// A directory, that is unique to a test and is removed after the test.
+struct TempDir(std::path::PathBuf);
+
+impl TempDir {
+    fn new(test: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("sub_cursor_{}_{}", std::process::id(), test));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn join(&self, path: &str) -> std::path::PathBuf { self.0.join(path) }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) { let _ = std::fs::remove_dir_all(&self.0); }
+}
 
 #[test]
 fn test_new() { SubCursor::new().start(20).end(100).preserve(true); }
@@ -137,6 +157,31 @@ fn test_write() {
     assert_eq!(sub_cursor.write(&[0, 1, 2, 3, 4]).unwrap(), 3);
 }
 
+#[test]
+fn test_write_position() {
+    let cursor = Arc::new(Mutex::new(Cursor::new(vec![0; 16])));
+    let mut sub_cursor = SubCursor::from(cursor.clone()).start(4).end(12);
+
+    assert_eq!(sub_cursor.write(&[1, 2, 3]).unwrap(), 3);
+    assert_eq!(sub_cursor.position(), 3);
+
+    // the position used to stay at the start, so the next write overwrote the
+    // previous one
+    assert_eq!(sub_cursor.write(&[9]).unwrap(), 1);
+    assert_eq!(&cursor.lock().unwrap().get_ref()[4..8], &[1, 2, 3, 9]);
+    sub_cursor.seek(SeekFrom::Start(3)).unwrap();
+
+    sub_cursor.write_all(&[4, 5]).unwrap();
+    assert_eq!(sub_cursor.position(), 5);
+    assert_eq!(
+        &cursor.lock().unwrap().get_ref()[4..12],
+        &[1, 2, 3, 4, 5, 0, 0, 0]
+    );
+
+    sub_cursor.write_all(&[6, 7, 8]).unwrap();
+    assert_eq!(sub_cursor.write(&[9]).unwrap(), 0);
+}
+
 #[test]
 fn test_len() {
     let sub_cursor = SubCursor::new().start(20).end(100).preserve(false);
@@ -156,3 +201,1696 @@ fn test_position() {
     assert_eq!(20, sub_cursor.seek(SeekFrom::Start(20)).unwrap());
     assert_eq!(sub_cursor.position(), 20);
 }
+
+#[test]
+fn test_resize() {
+    let cursor = Arc::new(Mutex::new(Cursor::new(b"headerbodytrailer".to_vec())));
+    let registry = Registry::new();
+
+    let root = SubCursor::from(cursor.clone()).end(17).registry(&registry);
+    let mut header = root.end(6);
+    let mut body = root.start(6).end(10);
+    let mut trailer = root.start(10);
+
+    body.resize(8);
+    assert_eq!(
+        cursor.lock().unwrap().get_ref(),
+        b"headerbody\0\0\0\0trailer"
+    );
+    header.sync();
+    trailer.sync();
+    assert_eq!((header.get_start(), header.get_end()), (0, 6));
+    assert_eq!((trailer.get_start(), trailer.get_end()), (14, 21));
+    assert_eq!(root.sub_cursor().len(), 21);
+
+    let mut result = String::new();
+    trailer.read_to_string(&mut result).unwrap();
+    assert_eq!(result, "trailer".to_string());
+
+    body.resize(2);
+    assert_eq!(cursor.lock().unwrap().get_ref(), b"headerbotrailer");
+    trailer.sync();
+    assert_eq!((trailer.get_start(), trailer.get_end()), (8, 15));
+
+    // SubCursors without a registry are not adjusted:
+    let unregistered = SubCursor::from(cursor.clone()).start(8).end(15);
+    body.resize(4);
+    assert_eq!(unregistered.get_start(), 8);
+    trailer.sync();
+    assert_eq!(trailer.get_start(), 10);
+}
+
+#[test]
+fn test_overlay() {
+    let cursor = Arc::new(Mutex::new(Cursor::new((0..10).collect::<Vec<u8>>())));
+    let mut overlay = SubCursor::from(cursor.clone()).start(2).end(8).overlay();
+
+    // writes are limited to the window
+    assert_eq!(overlay.seek(SeekFrom::Start(4)).unwrap(), 4);
+    assert_eq!(overlay.write(&[20, 21, 22, 23]).unwrap(), 2);
+    assert_eq!(overlay.write(&[24]).unwrap(), 0);
+    assert_eq!(overlay.dirty_ranges(), vec![4..6]);
+
+    let mut result = vec![];
+    overlay.seek(SeekFrom::Start(0)).unwrap();
+    overlay.read_to_end(&mut result).unwrap();
+    assert_eq!(result, vec![2, 3, 4, 5, 20, 21]);
+    assert_eq!(
+        cursor.lock().unwrap().get_ref(),
+        &(0..10).collect::<Vec<u8>>()
+    );
+
+    overlay.commit().unwrap();
+    assert!(!overlay.is_dirty());
+    assert_eq!(
+        cursor.lock().unwrap().get_ref(),
+        &[0, 1, 2, 3, 4, 5, 20, 21, 8, 9]
+    );
+}
+
+#[test]
+fn test_transaction() {
+    let cursor = Arc::new(Mutex::new(Cursor::new(vec![0; 8])));
+    let sub_cursor = SubCursor::from(cursor.clone()).start(2).end(6);
+
+    {
+        let mut transaction = sub_cursor.transaction();
+        transaction.write_all(&[1, 2]).unwrap();
+        // dropped without commit
+    }
+    assert_eq!(cursor.lock().unwrap().get_ref(), &[0; 8]);
+
+    let mut transaction = sub_cursor.transaction();
+    transaction.write_all(&[1, 2]).unwrap();
+
+    // reads inside the transaction see the changes
+    let mut result = vec![];
+    transaction.seek(SeekFrom::Start(0)).unwrap();
+    transaction.read_to_end(&mut result).unwrap();
+    assert_eq!(result, vec![1, 2, 0, 0]);
+
+    transaction.commit().unwrap();
+    assert_eq!(cursor.lock().unwrap().get_ref(), &[0, 0, 1, 2, 0, 0, 0, 0]);
+}
+
+#[test]
+fn test_dirty_tracker() {
+    let tracker = Arc::new(Mutex::new(DirtyTracker::new(Cursor::new(vec![0; 64]))));
+
+    let mut first = SubCursor::from(tracker.clone()).start(8).end(16);
+    let mut second = SubCursor::from(tracker.clone()).start(32).end(48);
+
+    first.write_all(&[1; 4]).unwrap();
+    second.seek(SeekFrom::Start(4)).unwrap();
+    second.write_all(&[2; 4]).unwrap();
+
+    // reads are not tracked
+    let mut buffer = [0; 8];
+    first.seek(SeekFrom::Start(0)).unwrap();
+    first.read_exact(&mut buffer).unwrap();
+
+    assert_eq!(tracker.lock().unwrap().dirty_ranges(), vec![8..12, 36..40]);
+
+    tracker.lock().unwrap().clear_dirty();
+    assert!(!tracker.lock().unwrap().is_dirty());
+}
+
+#[test]
+fn test_coverage_tracker() {
+    let directory = TempDir::new("test_coverage_tracker");
+    let path = directory.join("data");
+    std::fs::write(&path, (0..64).collect::<Vec<u8>>()).unwrap();
+
+    let file = std::fs::File::open(&path).unwrap();
+    let tracker = Arc::new(Mutex::new(CoverageTracker::new(file)));
+
+    let mut first = SubCursor::from(tracker.clone()).start(8).end(16);
+    let mut second = SubCursor::from(tracker.clone()).start(12).end(24);
+    let mut third = SubCursor::from(tracker.clone()).start(40).end(48);
+
+    let mut buffer = [0; 8];
+    first.read_exact(&mut buffer).unwrap();
+    assert_eq!(buffer, [8, 9, 10, 11, 12, 13, 14, 15]);
+
+    second.seek(SeekFrom::Start(2)).unwrap();
+    second.read_exact(&mut buffer[..6]).unwrap();
+    assert_eq!(&buffer[..6], &[14, 15, 16, 17, 18, 19]);
+
+    third.seek(SeekFrom::End(-2)).unwrap();
+    third.read_exact(&mut buffer[..2]).unwrap();
+    assert_eq!(&buffer[..2], &[46, 47]);
+
+    // reads past the end of a SubCursor are not forwarded to the stream
+    assert_eq!(third.read(&mut buffer).unwrap(), 0);
+
+    let tracker = tracker.lock().unwrap();
+    assert_eq!(
+        tracker.read_ranges(),
+        vec![(8..14, 1), (14..16, 2), (16..20, 1), (46..48, 1)]
+    );
+    assert_eq!(tracker.gaps(64), vec![0..8, 20..46, 48..64]);
+}
+
+#[test]
+fn test_record_and_replay() {
+    // a tiny "parser", that reads a header and a body from different windows
+    fn parse<T: Read + Seek>(root: &SubCursor<T>) -> ([u8; 4], [u8; 8]) {
+        let mut header = [0; 4];
+        root.end(4).read_exact(&mut header).unwrap();
+
+        let mut body = root.start(header[2] as usize).end(40);
+        body.seek(SeekFrom::Start(4)).unwrap();
+        let mut result = [0; 8];
+        body.read_exact(&mut result).unwrap();
+
+        (header, result)
+    }
+
+    let recorder = Arc::new(Mutex::new(Recorder::new(Cursor::new(
+        (0..64).collect::<Vec<u8>>(),
+    ))));
+
+    // SubCursors, that are created independently, share the recorder
+    let original = parse(&SubCursor::from(recorder.clone()).end(64));
+    SubCursor::from(recorder.clone())
+        .start(60)
+        .end(64)
+        .read_exact(&mut [0; 2])
+        .unwrap();
+
+    let reads = recorder
+        .lock()
+        .unwrap()
+        .events()
+        .iter()
+        .filter_map(|event| {
+            match event {
+                Event::Read { offset, data, .. } => Some((*offset, data.len())),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(reads, vec![(0, 4), (6, 8), (60, 2)]);
+
+    let mut file = vec![];
+    recorder.lock().unwrap().save(&mut file).unwrap();
+    let loaded = Replay::load(file.as_slice()).unwrap();
+    assert_eq!(loaded.events(), recorder.lock().unwrap().events());
+
+    // the offsets are absolute, so the replay can be used with other windows
+    let replay = Arc::new(Mutex::new(loaded));
+    assert_eq!(parse(&SubCursor::from(replay.clone()).end(64)), original);
+    let mut body = SubCursor::from(replay.clone()).start(6).end(14);
+    let mut result = vec![];
+    body.read_to_end(&mut result).unwrap();
+    assert_eq!(result, (6..14).collect::<Vec<u8>>());
+
+    // data, that has never been read, is not available
+    let mut unrecorded = SubCursor::from(replay.clone()).start(20).end(30);
+    assert!(unrecorded.read(&mut [0; 4]).is_err());
+
+    // a corrupted recording is rejected
+    let overflow = b"SCREC\x01\x00\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01\x02abc";
+    let unknown = b"SCREC\x01\x03\x00";
+    for file in &[&overflow[..], &unknown[..]] {
+        let error = Replay::load(*file).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn test_record_windows() {
+    let recorder = Arc::new(Mutex::new(Recorder::new(Cursor::new(
+        (0..16).collect::<Vec<u8>>(),
+    ))));
+    let root = SubCursor::from(recorder.clone()).recorded();
+
+    // both windows read the same bytes, only the window tells them apart
+    let mut first = root.start(4).end(8);
+    let mut second = root.start(2).end(8);
+    second.seek(SeekFrom::Start(2)).unwrap();
+    first.read_exact(&mut [0; 2]).unwrap();
+    second.read_exact(&mut [0; 2]).unwrap();
+    first.write_all(&[0xFF]).unwrap();
+
+    let events = recorder.lock().unwrap().events().to_vec();
+    let reads = events
+        .iter()
+        .filter_map(|event| {
+            match event {
+                Event::Read { offset, window, .. } => Some((*offset, window.clone())),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(reads, vec![(4, Some(4..8)), (4, Some(2..8))]);
+    assert!(events.iter().all(|event| event.window().is_some()));
+    assert_eq!(events.last().unwrap().window(), Some(&(4..8)));
+
+    // accesses, that do not come from a recorded SubCursor, have no window
+    recorder.lock().unwrap().read_exact(&mut [0; 1]).unwrap();
+    assert_eq!(
+        recorder.lock().unwrap().events().last().unwrap().window(),
+        None
+    );
+
+    // the windows survive saving and loading
+    let mut file = vec![];
+    recorder.lock().unwrap().save(&mut file).unwrap();
+    let loaded = Replay::load(file.as_slice()).unwrap();
+    assert_eq!(loaded.events(), recorder.lock().unwrap().events());
+}
+
+#[test]
+fn test_chain_cursor() {
+    let first = Arc::new(Mutex::new(Cursor::new(vec![0; 8])));
+    let second = Arc::new(Mutex::new(Cursor::new(vec![0; 8])));
+
+    let mut chain_cursor: ChainCursor<_> = vec![
+        SubCursor::from(first.clone()).start(2).end(5),
+        SubCursor::from(second.clone()).start(4).end(4),
+        SubCursor::from(second.clone()).start(1).end(7),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(chain_cursor.len(), 9);
+
+    // writes are split across the extents and limited to their windows
+    chain_cursor.seek(SeekFrom::Start(1)).unwrap();
+    assert_eq!(chain_cursor.write(&[1; 20]).unwrap(), 8);
+    assert_eq!(cursor_data(&first), vec![0, 0, 0, 1, 1, 0, 0, 0]);
+    assert_eq!(cursor_data(&second), vec![0, 1, 1, 1, 1, 1, 1, 0]);
+
+    assert_eq!(chain_cursor.seek(SeekFrom::End(-2)).unwrap(), 7);
+    let mut result = vec![];
+    chain_cursor.read_to_end(&mut result).unwrap();
+    assert_eq!(result, vec![1, 1]);
+
+    // reading after the end
+    chain_cursor.seek(SeekFrom::Start(20)).unwrap();
+    assert_eq!(chain_cursor.read(&mut [0; 4]).unwrap(), 0);
+    assert!(chain_cursor.seek(SeekFrom::Current(-30)).is_err());
+}
+
+fn cursor_data(cursor: &Arc<Mutex<Cursor<Vec<u8>>>>) -> Vec<u8> {
+    cursor.lock().unwrap().get_ref().clone()
+}
+
+#[test]
+fn test_multi_volume() {
+    let directory = TempDir::new("test_multi_volume");
+
+    std::fs::write(directory.join("data.08"), b"abcd").unwrap();
+    std::fs::write(directory.join("data.09"), b"").unwrap();
+    std::fs::write(directory.join("data.10"), b"efgh").unwrap();
+    std::fs::write(directory.join("data.11"), b"ij").unwrap();
+
+    let multi_volume = MultiVolume::open(directory.join("data.08")).unwrap();
+    assert_eq!(multi_volume.paths().count(), 4);
+    assert_eq!(multi_volume.len(), 10);
+
+    let mut sub_cursor = SubCursor::from(multi_volume).start(2).end(9);
+    let mut result = String::new();
+    sub_cursor.read_to_string(&mut result).unwrap();
+    assert_eq!(result, "cdefghi".to_string());
+
+    sub_cursor.seek(SeekFrom::End(-2)).unwrap();
+    let mut buffer = [0; 2];
+    sub_cursor.read_exact(&mut buffer).unwrap();
+    assert_eq!(&buffer, b"hi");
+
+    assert!(MultiVolume::open(directory.join("data")).is_err());
+}
+
+#[test]
+fn test_split() {
+    let cursor = Arc::new(Mutex::new(Cursor::new((0..20).collect::<Vec<u8>>())));
+    let sub_cursor = SubCursor::from(cursor.clone()).start(5).end(15);
+
+    let (mut left, mut right) = sub_cursor.split_at(4).unwrap();
+    let mut result = vec![];
+    left.read_to_end(&mut result).unwrap();
+    assert_eq!(result, vec![5, 6, 7, 8]);
+
+    // the children share the underlying stream
+    right.write_all(&[0xff]).unwrap();
+    assert_eq!(cursor.lock().unwrap().get_ref()[9], 0xff);
+
+    assert!(sub_cursor.split_at(10).is_ok());
+    assert!(sub_cursor.split_at(11).is_err());
+    assert!(sub_cursor.split_at_offsets(&[4, 2]).is_err());
+    assert!(sub_cursor.split_at_offsets(&[4, 11]).is_err());
+    assert!(sub_cursor.split_at_offsets(&[u64::MAX]).is_err());
+
+    let chunks = sub_cursor
+        .chunks(3)
+        .map(|chunk| (chunk.get_start(), chunk.get_end()))
+        .collect::<Vec<_>>();
+    assert_eq!(chunks, vec![(5, 8), (8, 11), (11, 14), (14, 15)]);
+    assert_eq!(sub_cursor.chunks(3).len(), 4);
+
+    let chunks_exact = sub_cursor.chunks_exact(3);
+    assert_eq!(chunks_exact.len(), 3);
+    assert_eq!(chunks_exact.remainder().get_start(), 14);
+    assert_eq!(chunks_exact.last().unwrap().get_end(), 14);
+
+    assert_eq!(SubCursor::new().chunks(3).count(), 0);
+    assert_eq!(sub_cursor.chunks(usize::MAX).size_hint(), (1, Some(1)));
+
+    // a SubCursor, that has been resized through another handle, is split at
+    // the bounds of its last use and the parts follow the resize
+    let registry = Registry::new();
+    let stale = SubCursor::from(b"aaabbbccc".to_vec())
+        .start(3)
+        .registry(&registry);
+    stale.sub_cursor().resize(4);
+    assert_eq!(stale.len(), 6);
+
+    let (mut left, mut right) = stale.split_at(5).unwrap();
+    let mut result = String::new();
+    left.read_to_string(&mut result).unwrap();
+    assert_eq!(result, "bbbc".to_string());
+    assert_eq!(right.read(&mut [0; 4]).unwrap(), 0);
+}
+
+#[test]
+fn test_record_cursor() {
+    #[derive(Debug, PartialEq)]
+    struct Entry {
+        id: u8,
+        value: u16,
+    }
+
+    impl Decode for Entry {
+        fn decode<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+            let mut buffer = [0; 3];
+            reader.read_exact(&mut buffer)?;
+            Ok(Self {
+                id: buffer[0],
+                value: u16::from_be_bytes([buffer[1], buffer[2]]),
+            })
+        }
+    }
+
+    impl Encode for Entry {
+        fn encode<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+            writer.write_all(&[self.id])?;
+            writer.write_all(&self.value.to_be_bytes())
+        }
+    }
+
+    let cursor = Arc::new(Mutex::new(Cursor::new(vec![
+        0xFF, 1, 0, 10, 2, 0, 20, 3, 0, 30, 0xFF,
+    ])));
+    let sub_cursor = SubCursor::from(cursor.clone()).start(1).end(10);
+
+    assert!(RecordCursor::new(sub_cursor.clone(), 3, 4).is_err());
+
+    let records = RecordCursor::new(sub_cursor, 3, 3).unwrap();
+    assert_eq!(records.len(), 3);
+    assert!(records.get(3).is_none());
+    assert_eq!(
+        records.decode::<Entry>(1).unwrap(),
+        Entry { id: 2, value: 20 }
+    );
+    assert!(records.decode::<Entry>(3).is_err());
+
+    let ids = records
+        .iter()
+        .rev()
+        .map(|record| record.get_start())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![7, 4, 1]);
+
+    records.set(2, &Entry { id: 4, value: 40 }).unwrap();
+    assert_eq!(
+        records.decode::<Entry>(2).unwrap(),
+        Entry { id: 4, value: 40 }
+    );
+    assert!(records.set(0, &[0; 2][..]).is_err());
+    assert_eq!(
+        cursor.lock().unwrap().get_ref(),
+        &vec![0xFF, 1, 0, 10, 2, 0, 20, 4, 0, 40, 0xFF]
+    );
+}
+
+#[test]
+fn test_find() {
+    let mut data = vec![0; 20_000];
+    data[8_190..8_194].copy_from_slice(b"MAGI");
+    data[19_000..19_004].copy_from_slice(b"MAGI");
+
+    let sub_cursor = SubCursor::from(data).start(100).end(19_100);
+
+    assert_eq!(sub_cursor.find(b"MAGI").unwrap(), Some(8_090));
+    assert_eq!(sub_cursor.rfind(b"MAGI").unwrap(), Some(18_900));
+    assert_eq!(
+        sub_cursor
+            .find_iter(b"MAGI")
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap(),
+        vec![8_090, 18_900]
+    );
+
+    // the match has to be completely inside of the window
+    let sub_cursor = sub_cursor.end(19_002);
+    assert_eq!(sub_cursor.rfind(b"MAGI").unwrap(), Some(8_090));
+
+    let mut found = sub_cursor.find_sub_cursor(b"MAGI").unwrap().unwrap();
+    let mut buffer = String::new();
+    found.read_to_string(&mut buffer).unwrap();
+    assert_eq!(buffer, "MAGI".to_string());
+}
+
+#[test]
+fn test_lines() {
+    let cursor = Arc::new(Mutex::new(Cursor::new(
+        b"HEADfirst\r\n\nthird\nlast".to_vec(),
+    )));
+    let sub_cursor = SubCursor::from(cursor.clone()).start(4).end(22);
+
+    let lines = sub_cursor
+        .lines()
+        .map(|line| line.map(|line| (line.get_start(), line.get_end())))
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(lines, vec![(4, 9), (11, 11), (12, 17), (18, 22)]);
+
+    // the lines are only read, when they are needed
+    let mut last = sub_cursor.lines().last().unwrap().unwrap();
+    cursor.lock().unwrap().get_mut()[18..22].copy_from_slice(b"LAST");
+
+    let mut buffer = String::new();
+    last.read_to_string(&mut buffer).unwrap();
+    assert_eq!(buffer, "LAST".to_string());
+
+    let records = sub_cursor
+        .split(b"\n")
+        .map(|record| record.unwrap().len())
+        .collect::<Vec<_>>();
+    assert_eq!(records, vec![6, 0, 5, 4]);
+
+    assert_eq!(SubCursor::new().lines().count(), 0);
+    assert_eq!(SubCursor::new().split(b",").count(), 1);
+}
+
+#[test]
+fn test_line_index() {
+    let directory = TempDir::new("test_line_index");
+
+    let source = directory.join("source.log");
+    let sidecar = directory.join("source.log.idx");
+    let data = (0..1000)
+        .map(|line| format!("line {}\r\n", line))
+        .collect::<String>();
+    std::fs::write(&source, &data).unwrap();
+
+    let index = LineIndex::open(&source, &sidecar, 64).unwrap();
+    assert_eq!(index.len(), 1000);
+    assert_eq!(LineIndex::open(&source, &sidecar, 64).unwrap(), index);
+
+    let sub_cursor = SubCursor::from(std::fs::File::open(&source).unwrap());
+    for line in &[0, 63, 64, 65, 999] {
+        let mut buffer = String::new();
+        index
+            .line(&sub_cursor, *line)
+            .unwrap()
+            .read_to_string(&mut buffer)
+            .unwrap();
+        assert_eq!(buffer, format!("line {}", line));
+    }
+    assert!(index.line(&sub_cursor, 1000).is_err());
+
+    let mut buffer = String::new();
+    index
+        .lines(&sub_cursor, 127..129)
+        .unwrap()
+        .read_to_string(&mut buffer)
+        .unwrap();
+    assert_eq!(buffer, "line 127\r\nline 128".to_string());
+
+    // the index is rebuilt after the source changed
+    std::fs::write(&source, b"a\nb\n").unwrap();
+    assert!(!index.is_valid(&std::fs::metadata(&source).unwrap()));
+    assert_eq!(LineIndex::open(&source, &sidecar, 64).unwrap().len(), 2);
+
+    // an index has to belong to the SubCursor
+    assert!(index.line(&SubCursor::from(b"a\nb\n".to_vec()), 0).is_err());
+}
+
+#[test]
+fn test_line_index_corrupt() {
+    let mut data = vec![];
+    LineIndex::build(&SubCursor::from(b"a\nb\nc\n".to_vec()), 2)
+        .unwrap()
+        .save(&mut data)
+        .unwrap();
+    assert_eq!(LineIndex::load(data.as_slice()).unwrap().len(), 3);
+
+    // magic, interval 2, delimiter `\n`, length 6, no modification time
+    let header = b"SCIDX\x01\x02\x01\n\x06\x00";
+    let max = b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01";
+
+    for corrupt in &[
+        // a huge delimiter
+        [&header[..7], &max[..]].concat(),
+        // too many lines
+        [&header[..], &max[..], b"\x00"].concat(),
+        // the checkpoints are not ascending
+        [&header[..], b"\x03\x02\x00\x00"].concat(),
+        // a checkpoint is after the end
+        [&header[..], b"\x03\x02\x00\x07"].concat(),
+        // a checkpoint overflows
+        [&header[..], b"\x03\x02\x04", &max[..]].concat(),
+    ] {
+        let error = LineIndex::load(corrupt.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn test_partition() {
+    let data = (0..100)
+        .map(|record| format!("{};{}\n", record, "x".repeat(record % 7)))
+        .collect::<String>();
+    let sub_cursor = SubCursor::from(data.clone().into_bytes()).start(3);
+
+    let parts = sub_cursor.partition(8, b"\n").unwrap();
+    assert_eq!(parts.len(), 8);
+    assert_eq!(parts[0].get_start(), 3);
+    assert_eq!(parts[7].get_end(), data.len());
+
+    for window in parts.windows(2) {
+        assert_eq!(window[0].get_end(), window[1].get_start());
+        assert_eq!(data.as_bytes()[window[0].get_end() - 1], b'\n');
+    }
+
+    // a single record can not be divided
+    let sub_cursor = SubCursor::from(b"aaaaaaaa\nb\n".to_vec());
+    let parts = sub_cursor.partition(4, b"\n").unwrap();
+    assert_eq!(
+        parts
+            .iter()
+            .map(|part| (part.get_start(), part.get_end()))
+            .collect::<Vec<_>>(),
+        vec![(0, 9), (9, 11)]
+    );
+
+    let directory = TempDir::new("test_partition");
+    std::fs::write(directory.join("data"), &data).unwrap();
+
+    let file = std::fs::File::open(directory.join("data")).unwrap();
+    let sub_cursor = SubCursor::from(PositionalFile::new(file).unwrap());
+    let records = sub_cursor
+        .par_partitions(16, b"\n", 4, |mut part| {
+            let mut buffer = String::new();
+            part.read_to_string(&mut buffer)?;
+            Ok(buffer
+                .lines()
+                .map(|line| line.split(';').next().unwrap().parse::<usize>().unwrap())
+                .collect::<Vec<_>>())
+        })
+        .unwrap();
+    assert_eq!(records.concat(), (0..100).collect::<Vec<_>>());
+
+    let result = sub_cursor.par_partitions(4, b"\n", 2, |part| {
+        if part.get_start() == 0 {
+            Err(std::io::Error::from(std::io::ErrorKind::Other))
+        } else {
+            Ok(())
+        }
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_reverse() {
+    let data = (0..5000)
+        .map(|line| format!("{}\n", line))
+        .collect::<String>();
+    let sub_cursor = SubCursor::from(data.clone().into_bytes()).start(10);
+
+    let mut reverse_reader = sub_cursor.reverse_reader();
+    assert_eq!(reverse_reader.remaining(), data.len() - 10);
+
+    let mut buffer = vec![];
+    reverse_reader.read_to_end(&mut buffer).unwrap();
+    buffer.reverse();
+    assert_eq!(buffer, data.as_bytes()[10..].to_vec());
+    assert_eq!(reverse_reader.remaining(), 0);
+
+    let lines = sub_cursor
+        .rlines()
+        .map(|line| {
+            let mut buffer = String::new();
+            line.unwrap().read_to_string(&mut buffer).unwrap();
+            buffer
+        })
+        .collect::<Vec<_>>();
+    let mut expected = data[10..].lines().map(String::from).collect::<Vec<_>>();
+    expected.reverse();
+    assert_eq!(lines, expected);
+
+    let sub_cursor = SubCursor::from(b"a\r\n\nb".to_vec());
+    let lines = sub_cursor
+        .rlines()
+        .map(|line| line.map(|line| (line.get_start(), line.get_end())))
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(lines, vec![(4, 5), (3, 3), (0, 1)]);
+
+    assert_eq!(SubCursor::new().rlines().count(), 0);
+    assert_eq!(SubCursor::new().rsplit(b",").count(), 1);
+}
+
+/// Creates a zip file with the provided entries (name, data, deflate).
+#[cfg(feature = "zip")]
+fn zip_file(prefix: &[u8], entries: &[(&str, &[u8], bool)], zip64: bool) -> Vec<u8> {
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+
+    let mut result = prefix.to_vec();
+    let mut central_directory = vec![];
+
+    for (name, data, deflate) in entries {
+        let (method, compressed) = {
+            if *deflate {
+                let mut encoder = DeflateEncoder::new(vec![], Compression::default());
+                encoder.write_all(data).unwrap();
+                (8_u16, encoder.finish().unwrap())
+            } else {
+                (0, data.to_vec())
+            }
+        };
+
+        let offset = (result.len() - prefix.len()) as u64;
+        result.extend_from_slice(b"PK\x03\x04\x14\0\0\0");
+        result.extend_from_slice(&method.to_le_bytes());
+        result.extend_from_slice(&[0; 8]);
+        result.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        result.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        result.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        result.extend_from_slice(&[0; 2]);
+        result.extend_from_slice(name.as_bytes());
+        result.extend_from_slice(&compressed);
+
+        central_directory.extend_from_slice(b"PK\x01\x02\x14\0\x14\0\0\0");
+        central_directory.extend_from_slice(&method.to_le_bytes());
+        central_directory.extend_from_slice(&[0; 8]);
+        if zip64 {
+            central_directory.extend_from_slice(&[0xFF; 8]);
+        } else {
+            central_directory.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+            central_directory.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        }
+        central_directory.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        central_directory.extend_from_slice(&(if zip64 { 28_u16 } else { 0 }).to_le_bytes());
+        central_directory.extend_from_slice(&[0; 10]);
+        if zip64 {
+            central_directory.extend_from_slice(&[0xFF; 4]);
+        } else {
+            central_directory.extend_from_slice(&(offset as u32).to_le_bytes());
+        }
+        central_directory.extend_from_slice(name.as_bytes());
+        if zip64 {
+            central_directory.extend_from_slice(&[1, 0, 24, 0]);
+            central_directory.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            central_directory.extend_from_slice(&(compressed.len() as u64).to_le_bytes());
+            central_directory.extend_from_slice(&offset.to_le_bytes());
+        }
+    }
+
+    let offset = (result.len() - prefix.len()) as u64;
+    let size = central_directory.len() as u64;
+    let count = entries.len() as u64;
+    result.extend_from_slice(&central_directory);
+
+    if zip64 {
+        let position = (result.len() - prefix.len()) as u64;
+        result.extend_from_slice(b"PK\x06\x06");
+        result.extend_from_slice(&44_u64.to_le_bytes());
+        result.extend_from_slice(&[0x2d, 0, 0x2d, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        result.extend_from_slice(&count.to_le_bytes());
+        result.extend_from_slice(&count.to_le_bytes());
+        result.extend_from_slice(&size.to_le_bytes());
+        result.extend_from_slice(&offset.to_le_bytes());
+
+        result.extend_from_slice(b"PK\x06\x07\0\0\0\0");
+        result.extend_from_slice(&position.to_le_bytes());
+        result.extend_from_slice(&1_u32.to_le_bytes());
+
+        result.extend_from_slice(b"PK\x05\x06\0\0\0\0");
+        result.extend_from_slice(&[0xFF; 12]);
+    } else {
+        result.extend_from_slice(b"PK\x05\x06\0\0\0\0");
+        result.extend_from_slice(&(count as u16).to_le_bytes());
+        result.extend_from_slice(&(count as u16).to_le_bytes());
+        result.extend_from_slice(&(size as u32).to_le_bytes());
+        result.extend_from_slice(&(offset as u32).to_le_bytes());
+    }
+
+    // a comment, that looks like the end of central directory record
+    result.extend_from_slice(&[6, 0]);
+    result.extend_from_slice(b"PK\x05\x06..");
+    result
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn test_zip_archive() {
+    use sub_cursor::{CompressionMethod, ZipArchive};
+
+    let text = "Hello World! ".repeat(100);
+    let entries = [
+        ("text.txt", text.as_bytes(), true),
+        ("dir/", &b""[..], false),
+        ("dir/raw.bin", &b"\x00\x01\x02"[..], false),
+    ];
+
+    for (prefix, zip64) in &[
+        (&b""[..], false),
+        (&b"#!/bin/sh\n"[..], false),
+        (&b""[..], true),
+    ] {
+        let data = zip_file(prefix, &entries, *zip64);
+        let archive = ZipArchive::new(SubCursor::from(data)).unwrap();
+
+        assert_eq!(archive.len(), 3);
+        assert_eq!(archive.comment(), b"PK\x05\x06..");
+        assert!(archive.by_name("dir/").unwrap().is_dir());
+
+        let entry = archive.by_name("text.txt").unwrap();
+        assert_eq!(entry.compression_method(), CompressionMethod::Deflated);
+        assert_eq!(entry.size(), text.len() as u64);
+        assert!(entry.compressed_size() < entry.size());
+
+        let mut result = String::new();
+        archive
+            .reader(entry)
+            .unwrap()
+            .read_to_string(&mut result)
+            .unwrap();
+        assert_eq!(result, text);
+
+        // a stored entry is a window of the archive
+        let entry = archive.by_name("dir/raw.bin").unwrap();
+        let data = archive.data(entry).unwrap();
+        assert_eq!(data.len(), 3);
+        let text_len = archive.entries()[0].compressed_size() as usize;
+        assert_eq!(
+            data.get_start(),
+            prefix.len() + 3 * 30 + 8 + text_len + 4 + 11
+        );
+    }
+
+    assert!(ZipArchive::new(SubCursor::from(b"not a zip file".to_vec())).is_err());
+}
+
+#[cfg(feature = "tar")]
+#[test]
+fn test_tar() {
+    use sub_cursor::{EntryType, TarArchive, TarHeader, TarWriter};
+
+    // the archive starts after some other data
+    let cursor = Arc::new(Mutex::new(Cursor::new(b"HEAD".to_vec())));
+    let sub_cursor = SubCursor::from(cursor.clone()).start(4);
+
+    let long_path = format!("{}/{}", "a".repeat(150), "b".repeat(150));
+    let mut writer = TarWriter::new(sub_cursor);
+    writer
+        .append(&TarHeader::new("small.txt").mtime(1_000), &b"small"[..])
+        .unwrap();
+    writer
+        .append(&TarHeader::new(long_path.clone()), &[1; 1024][..])
+        .unwrap();
+    writer
+        .append(
+            &TarHeader::new("link")
+                .kind(EntryType::Symlink)
+                .link_name("small.txt"),
+            &b""[..],
+        )
+        .unwrap();
+    let archive = writer.finish().unwrap();
+
+    assert_eq!(archive.get_start(), 4);
+    // header + 1 block, pax header + 1 block, header + 2 blocks, header, end
+    assert_eq!(archive.len(), 512 * (2 + 2 + 3 + 1 + 2));
+
+    let archive = TarArchive::new(archive).unwrap();
+    assert_eq!(archive.len(), 3);
+
+    let entry = archive.by_path("small.txt").unwrap();
+    assert_eq!(entry.header().get_mtime(), 1_000);
+    assert_eq!((entry.header_offset(), entry.data_offset()), (0, 512));
+
+    let entry = archive.by_path(&long_path).unwrap();
+    assert_eq!(entry.header_offset(), 1024);
+    assert_eq!(entry.data_offset(), 2560);
+
+    let mut data = vec![];
+    archive.data(entry).unwrap().read_to_end(&mut data).unwrap();
+    assert_eq!(data, vec![1; 1024]);
+
+    let entry = archive.by_path("link").unwrap();
+    assert_eq!(entry.header().get_kind(), EntryType::Symlink);
+    assert_eq!(entry.header().get_link_name(), "small.txt");
+
+    // the padding is zeroed
+    assert_eq!(
+        &cursor.lock().unwrap().get_ref()[4 + 512 + 5..4 + 1024],
+        &[0; 507][..]
+    );
+
+    let mut corrupted = cursor.lock().unwrap().get_ref().clone();
+    corrupted[4] = b'S';
+    assert!(TarArchive::new(SubCursor::from(corrupted).start(4)).is_err());
+
+    // the padding of a PAX header is zeroed too
+    let cursor = Arc::new(Mutex::new(Cursor::new(vec![0xFF; 2048])));
+    TarWriter::new(SubCursor::from(cursor.clone()))
+        .append(&TarHeader::new(long_path), &b""[..])
+        .unwrap();
+    let pax_data = cursor.lock().unwrap().get_ref()[512..1024].to_vec();
+    // the record is "311 path=...\n"
+    assert_eq!(pax_data.iter().rposition(|byte| *byte != 0), Some(310));
+    assert_eq!(pax_data[310], b'\n');
+}
+
+#[cfg(feature = "ar")]
+fn ar_header(name: &str, size: usize) -> Vec<u8> {
+    format!(
+        "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+        name, 1_500_000_000, 1000, 100, 100_644, size
+    )
+    .into_bytes()
+}
+
+#[cfg(feature = "ar")]
+#[test]
+fn test_ar() {
+    use sub_cursor::ArArchive;
+
+    let long_name = "a_very_long_object_file_name.o";
+
+    let mut data = b"!<arch>\n".to_vec();
+    // GNU symbol table
+    data.extend(ar_header("/", 4));
+    data.extend_from_slice(&[0; 4]);
+    // GNU table of long names
+    let names = format!("{}/\nother_long_name_in_table.o/\n", long_name);
+    data.extend(ar_header("//", names.len()));
+    data.extend_from_slice(names.as_bytes());
+    data.extend(ar_header("short.o/", 3));
+    data.extend_from_slice(b"abc\n");
+    let first_long = data.len() as u64;
+    data.extend(ar_header("/0", 2));
+    data.extend_from_slice(b"de");
+    data.extend(ar_header(&format!("/{}", long_name.len() + 2), 1));
+    data.extend_from_slice(b"f\n");
+    // BSD long name in front of the data
+    data.extend(ar_header("#1/20", 23));
+    data.extend_from_slice(b"bsd_long_name.o\0\0\0\0\0ghi\n");
+
+    let archive = ArArchive::new(SubCursor::from(data.clone())).unwrap();
+    let names = archive
+        .entries()
+        .iter()
+        .map(|entry| entry.name())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            "short.o",
+            long_name,
+            "other_long_name_in_table.o",
+            "bsd_long_name.o"
+        ]
+    );
+
+    let entry = archive.by_name(long_name).unwrap();
+    assert_eq!(entry.header_offset(), first_long);
+    assert_eq!(entry.data_offset(), first_long + 60);
+    assert_eq!(
+        (entry.mtime(), entry.uid(), entry.gid()),
+        (1_500_000_000, 1000, 100)
+    );
+    assert_eq!(entry.mode(), 0o100_644);
+
+    let read = |name| {
+        let mut result = String::new();
+        let entry = archive.by_name(name).unwrap();
+        archive
+            .data(entry)
+            .unwrap()
+            .read_to_string(&mut result)
+            .unwrap();
+        result
+    };
+    assert_eq!(read("short.o"), "abc");
+    assert_eq!(read("other_long_name_in_table.o"), "f");
+    assert_eq!(read("bsd_long_name.o"), "ghi");
+
+    // BSD stores the name of its symbol table in front of the data too
+    let mut bsd = b"!<arch>\n".to_vec();
+    bsd.extend(ar_header("#1/20", 28));
+    bsd.extend_from_slice(b"__.SYMDEF SORTED\0\0\0\0");
+    bsd.extend_from_slice(&[0; 8]);
+    bsd.extend(ar_header("#1/12", 14));
+    bsd.extend_from_slice(b"bsd_member.oxy");
+    let archive = ArArchive::new(SubCursor::from(bsd)).unwrap();
+    assert_eq!(archive.len(), 1);
+    assert_eq!(archive.entries()[0].name(), "bsd_member.o");
+    assert_eq!(archive.entries()[0].size(), 2);
+
+    // a member, that ends after the archive
+    let mut truncated = b"!<arch>\n".to_vec();
+    truncated.extend_from_slice(&ar_header("short.o", 100));
+    truncated.extend_from_slice(b"abc");
+    let error = ArArchive::new(SubCursor::from(truncated)).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    data[0] = b'?';
+    assert!(ArArchive::new(SubCursor::from(data)).is_err());
+}
+
+#[cfg(all(feature = "ar", feature = "tar"))]
+#[test]
+fn test_deb() {
+    use sub_cursor::{ArArchive, TarArchive, TarHeader, TarWriter};
+
+    let mut writer = TarWriter::new(SubCursor::new());
+    writer
+        .append(&TarHeader::new("./usr/bin/hello"), &b"binary"[..])
+        .unwrap();
+    let mut tar = vec![];
+    writer.finish().unwrap().read_to_end(&mut tar).unwrap();
+
+    let mut deb = b"!<arch>\n".to_vec();
+    deb.extend(ar_header("debian-binary", 4));
+    deb.extend_from_slice(b"2.0\n");
+    deb.extend(ar_header("data.tar", tar.len()));
+    deb.extend_from_slice(&tar);
+
+    let package = ArArchive::new(SubCursor::from(deb)).unwrap();
+    let data = package.data(package.by_name("data.tar").unwrap()).unwrap();
+    assert_eq!(data.get_start(), 8 + 60 + 4 + 60);
+
+    // the tar archive is opened as a nested window
+    let archive = TarArchive::new(data).unwrap();
+    let entry = archive.by_path("./usr/bin/hello").unwrap();
+
+    let mut result = String::new();
+    archive
+        .data(entry)
+        .unwrap()
+        .read_to_string(&mut result)
+        .unwrap();
+    assert_eq!(result, "binary");
+    assert_eq!(
+        archive.data(entry).unwrap().get_start(),
+        8 + 60 + 4 + 60 + 512
+    );
+}
+
+#[cfg(feature = "riff")]
+#[test]
+fn test_riff() {
+    let mut data = b"RIFF\x35\0\0\0WAVE".to_vec();
+    data.extend_from_slice(b"fmt \x04\0\0\0\x01\0\x02\0");
+    data.extend_from_slice(b"LIST\x12\0\0\0INFO");
+    data.extend_from_slice(b"INAM\x05\0\0\0title\0");
+    data.extend_from_slice(b"data\x03\0\0\0abc");
+
+    let cursor = Arc::new(Mutex::new(Cursor::new(data.clone())));
+    let sub_cursor = SubCursor::from(cursor.clone()).end(data.len());
+
+    let chunks = sub_cursor
+        .riff_walk()
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+    let ids = chunks
+        .iter()
+        .map(|chunk| (chunk.id(), chunk.depth(), chunk.size()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        vec![
+            (*b"RIFF", 0, 0x35),
+            (*b"fmt ", 1, 4),
+            (*b"LIST", 1, 0x12),
+            (*b"INAM", 2, 5),
+            (*b"data", 1, 3),
+        ]
+    );
+    assert_eq!(chunks[2].form_type(), Some(*b"INFO"));
+    assert_eq!(chunks[4].form_type(), None);
+    assert!(chunks[4].children().is_err());
+
+    // the padding byte after the odd sized chunk is skipped
+    assert_eq!(chunks[4].data().get_start(), data.len() - 3);
+
+    let mut title = String::new();
+    chunks[3]
+        .data()
+        .sub_cursor()
+        .read_to_string(&mut title)
+        .unwrap();
+    assert_eq!(title, "title");
+
+    chunks[3].rewrite(b"TITLE").unwrap();
+    assert!(chunks[3].rewrite(b"title\0").is_err());
+    assert_eq!(&cursor.lock().unwrap().get_ref()[44..50], b"TITLE\0");
+
+    // only the chunks directly inside of the window
+    assert_eq!(sub_cursor.riff_chunks().count(), 1);
+
+    let mut truncated = data;
+    truncated.truncate(50);
+    let result = SubCursor::from(truncated)
+        .riff_walk()
+        .collect::<std::io::Result<Vec<_>>>();
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "bmff")]
+fn iso_box(kind: &[u8], payload: &[u8]) -> Vec<u8> {
+    let mut result = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+    result.extend_from_slice(kind);
+    result.extend_from_slice(payload);
+    result
+}
+
+#[cfg(feature = "bmff")]
+#[test]
+fn test_iso_boxes() {
+    let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1];
+    stsd.extend(iso_box(b"avc1", b"entry"));
+    let stbl = [iso_box(b"stsd", &stsd), iso_box(b"stsz", &[0; 12])].concat();
+    let trak = iso_box(b"trak", &iso_box(b"stbl", &stbl));
+
+    let mut uuid = [0xAB; 16].to_vec();
+    uuid.extend_from_slice(b"xmp");
+
+    let mut data = iso_box(b"ftyp", b"isom");
+    data.extend(iso_box(b"moov", &trak));
+    data.extend(iso_box(b"uuid", &uuid));
+    let mdat_offset = data.len() as u64;
+    // a box with a 64-bit size
+    data.extend_from_slice(&[0, 0, 0, 1]);
+    data.extend_from_slice(b"mdat");
+    data.extend_from_slice(&20_u64.to_be_bytes());
+    data.extend_from_slice(b"data");
+    data.extend(iso_box(b"free", b""));
+
+    let sub_cursor = SubCursor::from(data.clone());
+    let boxes = sub_cursor
+        .iso_boxes()
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+    let kinds = boxes
+        .iter()
+        .map(|iso_box| iso_box.kind())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![*b"ftyp", *b"moov", *b"uuid", *b"mdat", *b"free"]
+    );
+
+    assert_eq!(boxes[2].user_type(), Some([0xAB; 16]));
+    assert_eq!(boxes[2].data().len(), 3);
+    assert_eq!(boxes[2].header_size(), 24);
+
+    let mdat = &boxes[3];
+    assert_eq!(
+        (mdat.offset(), mdat.header_size(), mdat.size()),
+        (mdat_offset, 16, 20)
+    );
+    let mut payload = String::new();
+    mdat.data()
+        .sub_cursor()
+        .read_to_string(&mut payload)
+        .unwrap();
+    assert_eq!(payload, "data");
+
+    // descend into moov/trak/stbl/stsd
+    let trak = boxes[1].children().next().unwrap().unwrap();
+    let stbl = trak.children().next().unwrap().unwrap();
+    let stsd = stbl.children().next().unwrap().unwrap();
+    assert_eq!(stsd.kind(), *b"stsd");
+    assert_eq!(stsd.full_box_header().unwrap(), (0, 0));
+
+    let entry = stsd.children_from(8).unwrap().next().unwrap().unwrap();
+    assert_eq!(entry.kind(), *b"avc1");
+    assert_eq!(entry.data().get_start(), 8 + 4 + 8 * 4 + 8 + 8);
+    assert!(stsd.children_from(100).is_err());
+
+    // the size is larger than the file
+    let result = SubCursor::from(iso_box(b"moov", b"abc")[..10].to_vec())
+        .iso_boxes()
+        .collect::<std::io::Result<Vec<_>>>();
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn test_png_chunks() {
+    fn chunk(kind: &[u8], data: &[u8], crc: u32) -> Vec<u8> {
+        let mut result = (data.len() as u32).to_be_bytes().to_vec();
+        result.extend_from_slice(kind);
+        result.extend_from_slice(data);
+        result.extend_from_slice(&crc.to_be_bytes());
+        result
+    }
+
+    let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+    data.extend(chunk(
+        b"IHDR",
+        &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0],
+        0x3a7e_9b55,
+    ));
+    data.extend(chunk(b"tEXt", &[b'x'; 10_000], 0xe5d3_7457));
+    data.extend(chunk(b"IDAT", b"pixels", 0x4f25_84a8));
+    data.extend(chunk(b"IEND", b"", 0xae42_6082));
+    // data after the end is ignored
+    data.extend_from_slice(b"trailing");
+
+    let sub_cursor = SubCursor::from(data.clone());
+    let chunks = sub_cursor
+        .png_chunks()
+        .unwrap()
+        .verify_crc(true)
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+
+    let kinds = chunks.iter().map(|chunk| chunk.kind()).collect::<Vec<_>>();
+    assert_eq!(kinds, vec![*b"IHDR", *b"tEXt", *b"IDAT", *b"IEND"]);
+    assert_eq!(chunks[1].crc(), 0xe5d3_7457);
+    assert!(!chunks[1].is_critical());
+    assert!(chunks[2].is_critical());
+
+    // strip the ancillary chunks by copying the others
+    let mut stripped = b"\x89PNG\r\n\x1a\n".to_vec();
+    for chunk in chunks.iter().filter(|chunk| chunk.is_critical()) {
+        chunk
+            .chunk()
+            .sub_cursor()
+            .read_to_end(&mut stripped)
+            .unwrap();
+    }
+    assert_eq!(stripped.len(), 8 + 25 + 18 + 12);
+    assert_eq!(SubCursor::from(stripped).png_chunks().unwrap().count(), 3);
+
+    // a corrupted byte in the data
+    let mut corrupted = data.clone();
+    corrupted[8 + 25 + 8 + 5_000] = b'y';
+    let result = SubCursor::from(corrupted.clone())
+        .png_chunks()
+        .unwrap()
+        .verify_crc(true)
+        .collect::<std::io::Result<Vec<_>>>();
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    // is only detected, if the crc is verified
+    assert_eq!(SubCursor::from(corrupted).png_chunks().unwrap().count(), 4);
+
+    data[1] = b'p';
+    assert!(SubCursor::from(data).png_chunks().is_err());
+}
+
+#[cfg(feature = "elf")]
+#[test]
+fn test_elf() {
+    use sub_cursor::{ElfFile, Endian};
+
+    // a 32-bit big endian file: header, program header, section data, names
+    // and section headers
+    let names = b"\0.debug_info\0.bss\0.shstrtab\0";
+    let mut data = vec![0; 52];
+    data[..6].copy_from_slice(b"\x7fELF\x01\x02");
+    data[16..18].copy_from_slice(&2_u16.to_be_bytes());
+    data[18..20].copy_from_slice(&8_u16.to_be_bytes());
+    data[24..28].copy_from_slice(&0x0040_0000_u32.to_be_bytes());
+    data[28..32].copy_from_slice(&52_u32.to_be_bytes());
+    let section_offset = 52 + 32 + 5 + names.len() as u32;
+    data[32..36].copy_from_slice(&section_offset.to_be_bytes());
+    // program header and section header sizes and counts, names index
+    for (index, value) in [32_u16, 1, 40, 4, 3].iter().enumerate() {
+        data[42 + 2 * index..44 + 2 * index].copy_from_slice(&value.to_be_bytes());
+    }
+
+    // a PT_NOTE segment, that covers the debug info
+    for value in &[4_u32, 84, 0, 0, 5, 5, 4, 1] {
+        data.extend_from_slice(&value.to_be_bytes());
+    }
+    data.extend_from_slice(b"debug");
+    data.extend_from_slice(names);
+
+    let section = |name: u32, kind: u32, offset: u32, size: u32| {
+        [name, kind, 0, 0, offset, size, 0, 0, 1, 0]
+            .iter()
+            .flat_map(|value| value.to_be_bytes().to_vec())
+            .collect::<Vec<_>>()
+    };
+    data.extend(section(0, 0, 0, 0));
+    data.extend(section(1, 1, 84, 5));
+    data.extend(section(13, 8, 89, 100));
+    data.extend(section(18, 3, 89, names.len() as u32));
+
+    let elf = ElfFile::new(SubCursor::from(data.clone())).unwrap();
+    assert!(!elf.is_64());
+    assert_eq!(elf.endian(), Endian::Big);
+    assert_eq!(
+        (elf.kind(), elf.machine(), elf.entry()),
+        (2, 8, 0x0040_0000)
+    );
+
+    let names = elf
+        .sections()
+        .iter()
+        .map(|section| section.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["", ".debug_info", ".bss", ".shstrtab"]);
+
+    let debug_info = elf.section_by_name(".debug_info").unwrap();
+    assert_eq!((debug_info.offset(), debug_info.size()), (84, 5));
+    let mut result = String::new();
+    elf.section_data(debug_info)
+        .unwrap()
+        .read_to_string(&mut result)
+        .unwrap();
+    assert_eq!(result, "debug");
+
+    // .bss does not occupy space in the file
+    let bss = elf.section_by_name(".bss").unwrap();
+    assert_eq!(bss.size(), 100);
+    assert_eq!(elf.section_data(bss).unwrap().len(), 0);
+
+    let note = &elf.segments()[0];
+    assert_eq!((note.kind(), note.flags(), note.memory_size()), (4, 4, 5));
+    assert_eq!(elf.segment_data(note).unwrap().get_start(), 84);
+
+    // section names, that are out of bounds
+    let mut corrupted = data.clone();
+    let len = corrupted.len();
+    corrupted[len - 20..len - 16].copy_from_slice(&1_000_u32.to_be_bytes());
+    let error = ElfFile::new(SubCursor::from(corrupted)).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // a section header table, that is out of bounds
+    data.truncate(data.len() - 1);
+    assert!(ElfFile::new(SubCursor::from(data)).is_err());
+}
+
+#[cfg(feature = "pe")]
+#[test]
+fn test_pe() {
+    use sub_cursor::PeFile;
+
+    fn put(data: &mut Vec<u8>, offset: usize, bytes: &[u8]) {
+        if data.len() < offset + bytes.len() {
+            data.resize(offset + bytes.len(), 0);
+        }
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    // a PE32 file with a DOS stub
+    let mut data = vec![];
+    put(&mut data, 0, b"MZ");
+    put(&mut data, 0x3c, &0x80_u32.to_le_bytes());
+    put(&mut data, 0x80, b"PE\0\0");
+    put(&mut data, 0x84, &0x14c_u16.to_le_bytes());
+    put(&mut data, 0x86, &2_u16.to_le_bytes());
+    // the symbol table (no symbols) and the string table
+    put(&mut data, 0x8c, &0x600_u32.to_le_bytes());
+    put(&mut data, 0x600, &16_u32.to_le_bytes());
+    put(&mut data, 0x604, b".debug_info\0");
+    put(&mut data, 0x94, &224_u16.to_le_bytes());
+
+    // the optional header
+    put(&mut data, 0x98, &0x10b_u16.to_le_bytes());
+    put(&mut data, 0x98 + 16, &0x1010_u32.to_le_bytes());
+    put(&mut data, 0x98 + 28, &0x0040_0000_u32.to_le_bytes());
+    put(&mut data, 0x98 + 92, &16_u32.to_le_bytes());
+    // the debug directory is inside of .text
+    put(&mut data, 0x98 + 96 + 6 * 8, &0x1020_u32.to_le_bytes());
+    put(&mut data, 0x98 + 96 + 6 * 8 + 4, &28_u32.to_le_bytes());
+    // the certificate table uses a file offset
+    put(&mut data, 0x98 + 96 + 4 * 8, &0x500_u32.to_le_bytes());
+    put(&mut data, 0x98 + 96 + 4 * 8 + 4, &8_u32.to_le_bytes());
+
+    let section = |name: &[u8], virtual_size: u32, address: u32, raw_size: u32, offset: u32| {
+        let mut entry = name.to_vec();
+        entry.resize(8, 0);
+        for value in &[
+            virtual_size,
+            address,
+            raw_size,
+            offset,
+            0,
+            0,
+            0,
+            0x6000_0020,
+        ] {
+            entry.extend_from_slice(&value.to_le_bytes());
+        }
+        entry
+    };
+    let table = [
+        section(b".text", 0x100, 0x1000, 0x200, 0x200),
+        section(b"/4", 0x10, 0x2000, 0x200, 0x400),
+    ]
+    .concat();
+    put(&mut data, 0x98 + 224, &table);
+    put(&mut data, 0x220, b"debug directory");
+    put(&mut data, 0x500, b"signatur");
+
+    let pe = PeFile::new(SubCursor::from(data.clone())).unwrap();
+    assert_eq!(pe.machine(), 0x14c);
+    assert!(!pe.is_64());
+    assert_eq!((pe.entry_point(), pe.image_base()), (0x1010, 0x0040_0000));
+
+    let names = pe
+        .sections()
+        .iter()
+        .map(|section| section.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec![".text", ".debug_info"]);
+
+    let text = pe.section_by_name(".text").unwrap();
+    assert_eq!(pe.section_data(text).unwrap().get_start(), 0x200);
+    assert_eq!(pe.section_data(text).unwrap().len(), 0x200);
+
+    assert_eq!(pe.data_directories().len(), 16);
+    assert_eq!(pe.rva_to_offset(0x1020), Some(0x220));
+    assert_eq!(pe.rva_to_offset(0x3000), None);
+
+    let mut debug = vec![];
+    pe.directory_data(&pe.data_directories()[6])
+        .unwrap()
+        .read_to_end(&mut debug)
+        .unwrap();
+    assert_eq!(&debug[..15], b"debug directory");
+
+    let mut certificate = String::new();
+    pe.directory_data(&pe.data_directories()[4])
+        .unwrap()
+        .read_to_string(&mut certificate)
+        .unwrap();
+    assert_eq!(certificate, "signatur");
+
+    assert!(pe.data_directories()[0].is_empty());
+    assert!(pe.directory_data(&pe.data_directories()[0]).is_err());
+
+    // a string table, that is out of bounds
+    let error = PeFile::new(SubCursor::from(data[..data.len() - 1].to_vec())).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    data[0x81] = b'X';
+    assert!(PeFile::new(SubCursor::from(data)).is_err());
+}
+
+#[cfg(feature = "disk")]
+fn mbr_entry(sector: &mut [u8], index: usize, kind: u8, first_lba: u32, sector_count: u32) {
+    let entry = &mut sector[446 + 16 * index..462 + 16 * index];
+    entry[4] = kind;
+    entry[8..12].copy_from_slice(&first_lba.to_le_bytes());
+    entry[12..16].copy_from_slice(&sector_count.to_le_bytes());
+    sector[510..512].copy_from_slice(&[0x55, 0xAA]);
+}
+
+#[cfg(feature = "disk")]
+#[test]
+fn test_mbr() {
+    use sub_cursor::PartitionTable;
+
+    let mut image = vec![0; 64 * 512];
+    mbr_entry(&mut image, 0, 0x83, 1, 8);
+    image[446] = 0x80;
+    // an extended partition with two logical partitions
+    mbr_entry(&mut image, 1, 0x05, 16, 48);
+    mbr_entry(&mut image[16 * 512..], 0, 0x83, 2, 10);
+    mbr_entry(&mut image[16 * 512..], 1, 0x05, 20, 20);
+    mbr_entry(&mut image[36 * 512..], 0, 0x82, 4, 16);
+    image[(40 + 15) * 512..(40 + 16) * 512].copy_from_slice(&[7; 512]);
+
+    let table = PartitionTable::new(SubCursor::from(image.clone())).unwrap();
+    assert!(!table.is_gpt());
+    assert_eq!(table.sector_size(), 512);
+
+    let partitions = table
+        .partitions()
+        .iter()
+        .map(|partition| {
+            (
+                partition.number(),
+                partition.mbr_type().unwrap(),
+                partition.first_lba(),
+                partition.sector_count(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        partitions,
+        vec![
+            (1, 0x83, 1, 8),
+            (2, 0x05, 16, 48),
+            (5, 0x83, 18, 10),
+            (6, 0x82, 40, 16)
+        ]
+    );
+    assert!(table.partitions()[0].is_bootable());
+
+    let swap = table.data(&table.partitions()[3]).unwrap();
+    assert_eq!((swap.get_start(), swap.len()), (40 * 512, 16 * 512));
+    let mut last = vec![];
+    swap.sub_cursor().read_to_end(&mut last).unwrap();
+    assert_eq!(&last[15 * 512..], &[7; 512][..]);
+
+    // the second EBR points to the first one
+    mbr_entry(&mut image[36 * 512..], 1, 0x05, 0, 20);
+    assert!(PartitionTable::new(SubCursor::from(image)).is_err());
+}
+
+#[cfg(feature = "disk")]
+#[test]
+fn test_gpt() {
+    use sub_cursor::PartitionTable;
+
+    fn crc32(bytes: &[u8]) -> u32 {
+        !bytes.iter().fold(!0, |crc, byte| {
+            (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+                (crc >> 1) ^ (0xEDB8_8320 & (!(crc & 1)).wrapping_add(1))
+            })
+        })
+    }
+
+    let sector_size = 4096;
+    let sectors = 32;
+    let mut image = vec![0; sectors * sector_size];
+    mbr_entry(&mut image, 0, 0xEE, 1, sectors as u32 - 1);
+
+    let mut entries = vec![0; 4 * 128];
+    entries[..16].copy_from_slice(&[0xAF; 16]);
+    entries[16..32].copy_from_slice(&[1; 16]);
+    entries[32..40].copy_from_slice(&6_u64.to_le_bytes());
+    entries[40..48].copy_from_slice(&9_u64.to_le_bytes());
+    for (index, unit) in "root".encode_utf16().enumerate() {
+        entries[56 + 2 * index..58 + 2 * index].copy_from_slice(&unit.to_le_bytes());
+    }
+    // the second entry is unused
+    entries[256..272].copy_from_slice(&[0xBF; 16]);
+    entries[256 + 32..256 + 40].copy_from_slice(&10_u64.to_le_bytes());
+    entries[256 + 40..256 + 48].copy_from_slice(&10_u64.to_le_bytes());
+
+    let write_gpt = |image: &mut Vec<u8>, entries: &[u8], lba: usize, entries_lba: u64| {
+        if let Some(target) = (entries_lba as usize)
+            .checked_mul(sector_size)
+            .and_then(|offset| image.get_mut(offset..))
+            .and_then(|rest| rest.get_mut(..entries.len()))
+        {
+            target.copy_from_slice(entries);
+        }
+
+        let mut header = vec![0; 92];
+        header[..8].copy_from_slice(b"EFI PART");
+        header[12..16].copy_from_slice(&92_u32.to_le_bytes());
+        header[56..72].copy_from_slice(&[0xD1; 16]);
+        header[72..80].copy_from_slice(&entries_lba.to_le_bytes());
+        header[80..84].copy_from_slice(&4_u32.to_le_bytes());
+        header[84..88].copy_from_slice(&128_u32.to_le_bytes());
+        header[88..92].copy_from_slice(&crc32(entries).to_le_bytes());
+        let crc = crc32(&header);
+        header[16..20].copy_from_slice(&crc.to_le_bytes());
+
+        image[lba * sector_size..lba * sector_size + 92].copy_from_slice(&header);
+    };
+    write_gpt(&mut image, &entries, 1, 2);
+    write_gpt(&mut image, &entries, sectors - 1, sectors as u64 - 2);
+
+    let table = PartitionTable::with_sector_size(SubCursor::from(image.clone()), 4096).unwrap();
+    assert!(table.is_gpt());
+    assert!(!table.is_backup());
+    assert_eq!(table.disk_guid(), Some([0xD1; 16]));
+    assert_eq!(table.partitions().len(), 2);
+
+    let root = &table.partitions()[0];
+    assert_eq!(root.name(), "root");
+    assert_eq!(
+        (root.number(), root.first_lba(), root.sector_count()),
+        (1, 6, 4)
+    );
+    assert_eq!(
+        (root.type_guid(), root.guid()),
+        (Some([0xAF; 16]), Some([1; 16]))
+    );
+    assert_eq!(table.data(root).unwrap().get_start(), 6 * 4096);
+    assert_eq!(table.partitions()[1].number(), 3);
+
+    // the primary partition entries are out of bounds
+    for entries_lba in &[sectors as u64, u64::MAX / 2] {
+        let mut image = image.clone();
+        write_gpt(&mut image, &entries, 1, *entries_lba);
+
+        let table = PartitionTable::with_sector_size(SubCursor::from(image), 4096).unwrap();
+        assert!(table.is_backup());
+    }
+
+    // the last sector of a partition overflows
+    let mut overflow = entries.clone();
+    overflow[40..48].copy_from_slice(&u64::MAX.to_le_bytes());
+    let mut corrupted = image.clone();
+    write_gpt(&mut corrupted, &overflow, 1, 2);
+    write_gpt(&mut corrupted, &overflow, sectors - 1, sectors as u64 - 2);
+    let error = PartitionTable::with_sector_size(SubCursor::from(corrupted), 4096).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // a corrupted partition entry of the primary header
+    image[2 * sector_size + 33] = 0xFF;
+    let table = PartitionTable::with_sector_size(SubCursor::from(image.clone()), 4096).unwrap();
+    assert!(table.is_backup());
+    assert_eq!(table.partitions()[0].first_lba(), 6);
+
+    // both headers are corrupted
+    image[(sectors - 1) * sector_size] = b'X';
+    assert!(PartitionTable::with_sector_size(SubCursor::from(image), 4096).is_err());
+
+    // the sector is larger than the disk image
+    let error = PartitionTable::with_sector_size(SubCursor::from(vec![0; 4096]), 8192).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+    // the sector is too small or not a power of two
+    for sector_size in &[0, 256, 1000] {
+        let error = PartitionTable::with_sector_size(SubCursor::from(vec![0; 4096]), *sector_size)
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
+
+#[cfg(feature = "tiff")]
+#[test]
+fn test_tiff() {
+    use sub_cursor::{Endian, TiffFile};
+
+    fn ifd(entries: &[(u16, u16, u32, u32)], next: u32) -> Vec<u8> {
+        let mut result = (entries.len() as u16).to_be_bytes().to_vec();
+        for (tag, field_type, count, value) in entries {
+            result.extend_from_slice(&tag.to_be_bytes());
+            result.extend_from_slice(&field_type.to_be_bytes());
+            result.extend_from_slice(&count.to_be_bytes());
+            result.extend_from_slice(&value.to_be_bytes());
+        }
+        result.extend_from_slice(&next.to_be_bytes());
+        result
+    }
+
+    // header, first ifd (at 8), second ifd (at 64), exif ifd (at 96) and
+    // the values (at 128)
+    let mut data = b"MM\0*\0\0\0\x08".to_vec();
+    data.extend(ifd(
+        &[
+            // ImageDescription is stored out of line
+            (270, 2, 12, 128),
+            // two strips
+            (273, 4, 2, 140),
+            (279, 3, 2, 0x0004_0006),
+            (34665, 4, 1, 96),
+        ],
+        64,
+    ));
+    data.resize(64, 0);
+    data.extend(ifd(&[(256, 3, 1, 0x0010_0000)], 0));
+    data.resize(96, 0);
+    data.extend(ifd(&[(36864, 7, 4, u32::from_be_bytes(*b"0232"))], 0));
+    data.resize(128, 0);
+    data.extend_from_slice(b"description\0");
+    data.extend_from_slice(&[0, 0, 0, 148, 0, 0, 0, 152]);
+    data.extend_from_slice(b"abcdefghij");
+
+    let cursor = Arc::new(Mutex::new(Cursor::new(data.clone())));
+    let tiff = TiffFile::new(SubCursor::from(cursor.clone()).end(data.len())).unwrap();
+    assert_eq!(tiff.endian(), Endian::Big);
+
+    let offsets = tiff
+        .ifds()
+        .iter()
+        .map(|ifd| ifd.offset())
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, vec![8, 64]);
+
+    let first = &tiff.ifds()[0];
+    let description = first.entry(270).unwrap();
+    assert!(!description.is_inline());
+    assert_eq!(description.value_offset(), 128);
+    let mut text = String::new();
+    tiff.value(description)
+        .unwrap()
+        .read_to_string(&mut text)
+        .unwrap();
+    assert_eq!(text, "description\0");
+    assert!(tiff.integers(description).is_err());
+
+    let strips = tiff
+        .strips(first)
+        .unwrap()
+        .into_iter()
+        .map(|mut strip| {
+            let mut result = String::new();
+            strip.read_to_string(&mut result).unwrap();
+            result
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(strips, vec!["abcd".to_string(), "efghij".to_string()]);
+
+    let exif = tiff.sub_ifds(first.entry(34665).unwrap()).unwrap();
+    assert_eq!(exif.len(), 1);
+    let version = exif[0].entry(36864).unwrap();
+    assert!(version.is_inline());
+    assert_eq!(version.value_offset(), 96 + 2 + 8);
+
+    // update the width in place
+    let width = *tiff.ifds()[1].entry(256).unwrap();
+    assert_eq!(tiff.integers(&width).unwrap(), vec![16]);
+    tiff.set_integers(&width, &[1024]).unwrap();
+    assert_eq!(tiff.integers(&width).unwrap(), vec![1024]);
+    assert_eq!(&cursor.lock().unwrap().get_ref()[64 + 10..64 + 12], &[4, 0]);
+    assert!(tiff.set_integers(&width, &[70_000]).is_err());
+    assert!(tiff.set_integers(&width, &[1, 2]).is_err());
+    assert!(tiff.strips(&tiff.ifds()[1]).is_err());
+
+    // a huge count is rejected before the values are allocated
+    let mut huge = b"MM\0*\0\0\0\x08".to_vec();
+    huge.extend(ifd(&[(273, 16, u32::MAX, 0)], 0));
+    let huge = TiffFile::new(SubCursor::from(huge)).unwrap();
+    let offsets = huge.ifds()[0].entry(273).unwrap();
+    assert_eq!(
+        huge.integers(offsets).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+    assert!(huge.sub_ifds(offsets).is_err());
+
+    // the second ifd points to the first one
+    data[64 + 2 + 12..64 + 2 + 16].copy_from_slice(&8_u32.to_be_bytes());
+    let result = TiffFile::new(SubCursor::from(data));
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+}